@@ -5,6 +5,7 @@
 #include "ptr-utils.hpp"
 #include "method-utils.hpp"
 #include "field-utils.hpp"
+#include "event-utils.hpp"
 #include "unity-utils.hpp"
 
 #include "beatsaber-hook/shared/utils/byref.hpp"
@@ -0,0 +1,38 @@
+#pragma once
+
+#include "config.hpp"
+
+#include <functional>
+
+namespace {
+namespace cordl_internals {
+
+  /// @brief wrapper around the add/remove accessors of a C# event
+  /// allows `instance->event_Foo += handler;` and `instance->event_Foo -= handler;`
+  /// @tparam Delegate the delegate type of the event
+  template <typename Delegate>
+  struct EventW {
+    using accessor_t = std::function<void(Delegate)>;
+
+    EventW(accessor_t add, accessor_t remove) noexcept
+        : _add(std::move(add)), _remove(std::move(remove)) {}
+
+    /// @brief subscribe a handler to the event
+    EventW& operator+=(Delegate handler) {
+      _add(handler);
+      return *this;
+    }
+
+    /// @brief unsubscribe a handler from the event
+    EventW& operator-=(Delegate handler) {
+      _remove(handler);
+      return *this;
+    }
+
+  private:
+    accessor_t _add;
+    accessor_t _remove;
+  };
+
+} // namespace cordl_internals
+} // namespace
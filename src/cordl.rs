@@ -0,0 +1,1102 @@
+#![feature(entry_insert)]
+#![feature(let_chains)]
+#![feature(slice_as_chunks)]
+#![feature(read_buf)]
+#![feature(map_try_insert)]
+#![feature(lazy_cell)]
+#![feature(exit_status_error)]
+#![feature(iterator_try_collect)]
+
+#[cfg(feature = "il2cpp_v31")]
+extern crate brocolib_il2cpp_v31 as brocolib;
+
+#[cfg(feature = "il2cpp_v29")]
+extern crate brocolib_il2cpp_v29 as brocolib;
+
+use brocolib::global_metadata::TypeDefinitionIndex;
+use byteorder::LittleEndian;
+use color_eyre::eyre::{bail, eyre, Context};
+use generate::metadata::{
+    read_metadata_version, CordlMetadata, PointerSize, SUPPORTED_METADATA_VERSION,
+};
+use itertools::{Either, Itertools};
+
+use include_dir::{include_dir, Dir};
+use log::{info, trace, warn};
+use rayon::prelude::*;
+
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+    time,
+};
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::{
+    data::profile::{glob_match, GenerationProfile, DEFAULT_PROFILE},
+    generate::{
+        cs_context_collection::{TypeContextCollection, TypeFailures},
+        cs_type_tag::CsTypeTag,
+        graph::graph_main,
+        output::OutputStats,
+        report,
+    },
+};
+mod data;
+mod generate;
+// mod handlers;
+mod helpers;
+
+pub use generate::metadata::MetadataVersion;
+
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum TargetLang {
+    #[cfg(feature = "cpp")]
+    Cpp,
+    #[cfg(feature = "json")]
+    SingleJSON,
+    #[cfg(feature = "json")]
+    MultiJSON,
+    #[cfg(feature = "rust")]
+    Rust,
+    #[cfg(feature = "symbols")]
+    Symbols,
+    #[cfg(feature = "csharp")]
+    #[value(name = "csharp")]
+    CSharp,
+    #[cfg(feature = "c_header")]
+    CHeader,
+    #[cfg(feature = "frida")]
+    Frida,
+    #[cfg(feature = "docs")]
+    Docs,
+}
+
+#[derive(Parser)]
+#[clap(name = "cordl", author, version, about, long_about = None)]
+#[clap(subcommand_negates_reqs = true)]
+struct Cli {
+    /// The global-metadata.dat file to use
+    #[clap(short, long, value_parser, value_name = "FILE", required = true)]
+    metadata: Option<PathBuf>,
+
+    /// The libil2cpp.so file to use
+    #[clap(short, long, value_parser, value_name = "FILE", required = true)]
+    libil2cpp: Option<PathBuf>,
+
+    /// Whether to format
+    #[clap(short, long, global = true)]
+    format: bool,
+
+    #[clap(short, long)]
+    remove_verbose_comments: bool,
+
+    #[clap(value_parser, required = true)]
+    target: Option<TargetLang>,
+
+    /// Whether to generate generic method specializations
+    #[clap(short, long, global = true)]
+    gen_generic_methods_specializations: bool,
+
+    /// The profile (.json or .toml) listing game specific blacklisted types
+    #[clap(long, value_parser, value_name = "FILE", global = true)]
+    profile: Option<PathBuf>,
+
+    /// Pointer size of the binary in bytes (4 or 8), detected from the ELF header if omitted
+    #[clap(long, value_parser = parse_pointer_size, global = true)]
+    pointer_size: Option<PointerSize>,
+
+    /// The il2cpp metadata version, read from the global-metadata.dat header if omitted
+    #[clap(long, global = true)]
+    metadata_version: Option<u32>,
+
+    /// Only generate the types matching these globs and what they depend on
+    /// e.g `--only "HMUI.*" --only GlobalNamespace.MainFlowCoordinator`
+    #[clap(long, value_name = "PATTERN")]
+    only: Vec<String>,
+
+    /// The folder to write the output to, defaults to `./codegen` for C++, `./codegen-rs` for Rust,
+    /// `./multi_json`, `./symbols`, `./csharp`, `./c_header`, `./frida` and `./docs`
+    /// for those targets and the current folder for `cordl.json`
+    #[clap(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+
+    /// The C++ header folder, relative to the output folder, defaults to `include`
+    #[clap(long, value_name = "DIR")]
+    header_subdir: Option<PathBuf>,
+
+    /// The folder cordl_internals is copied to, relative to the C++ header folder,
+    /// defaults to `cordl_internals`
+    #[clap(long, value_name = "DIR")]
+    internals_dir: Option<PathBuf>,
+
+    /// Wrap the generated C++ types in an anonymous namespace
+    #[clap(long)]
+    anonymous_namespace: bool,
+
+    /// Write one C# file per namespace instead of a single `dump.cs`
+    #[clap(long)]
+    split_namespaces: bool,
+
+    /// The package name of the generated Rust crate, defaults to `bs_cordl`
+    #[clap(long)]
+    crate_name: Option<String>,
+
+    /// Print how many files were created, updated, left unchanged and deleted
+    #[clap(long, global = true)]
+    stats: bool,
+
+    /// Where to write the report of generated, skipped and failed types
+    #[clap(long, value_name = "FILE", default_value = "cordl-report.json")]
+    report: PathBuf,
+
+    #[clap(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Report added, removed and changed types between two versions of a game
+    Diff(DiffArgs),
+    /// Write the JSON Schema of the json targets, or check json output against the current format
+    Schema(SchemaArgs),
+    /// Write the inheritance, nesting and dependency graph of some types as DOT or GraphML
+    Graph(GraphArgs),
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// The global-metadata.dat file of the old version
+    #[clap(long, value_parser, value_name = "FILE")]
+    old_metadata: PathBuf,
+
+    /// The libil2cpp.so file of the old version
+    #[clap(long, value_parser, value_name = "FILE")]
+    old_libil2cpp: PathBuf,
+
+    /// The global-metadata.dat file of the new version
+    #[clap(long, value_parser, value_name = "FILE")]
+    new_metadata: PathBuf,
+
+    /// The libil2cpp.so file of the new version
+    #[clap(long, value_parser, value_name = "FILE")]
+    new_libil2cpp: PathBuf,
+
+    /// Write the text report to this file instead of stdout
+    #[clap(short, long, value_parser, value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Also write the report as json to this file
+    #[clap(long, value_parser, value_name = "FILE")]
+    json: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct SchemaArgs {
+    /// The folder to write the schemas to
+    #[clap(short, long, value_parser, value_name = "FOLDER", default_value = ".")]
+    output: PathBuf,
+
+    /// Validate a cordl.json file or multi json folder against the schemas instead of writing them
+    #[clap(long, value_parser, value_name = "PATH")]
+    validate: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct GraphArgs {
+    /// The global-metadata.dat file to use
+    #[clap(long, value_parser, value_name = "FILE")]
+    metadata: PathBuf,
+
+    /// The libil2cpp.so file to use
+    #[clap(long, value_parser, value_name = "FILE")]
+    libil2cpp: PathBuf,
+
+    /// The types to start from, globs like `--only`
+    #[clap(long, value_name = "PATTERN", required = true)]
+    root: Vec<String>,
+
+    /// The edges to follow
+    #[clap(
+        long,
+        value_delimiter = ',',
+        default_values = ["inherit", "implement", "field-uses", "method-uses", "nests"]
+    )]
+    edges: Vec<graph_main::EdgeKind>,
+
+    /// How many edges away from the roots to go, unlimited if omitted
+    #[clap(long)]
+    depth: Option<usize>,
+
+    /// Group the types by namespace
+    #[clap(long)]
+    cluster_namespaces: bool,
+
+    /// The format to write the graph in
+    #[clap(long, value_enum, default_value = "dot")]
+    graph_format: graph_main::GraphFormat,
+
+    /// Write the graph to this file instead of stdout
+    #[clap(short, long, value_parser, value_name = "FILE")]
+    output: Option<PathBuf>,
+}
+
+static INTERNALS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/../../cordl_internals");
+
+pub type Endian = LittleEndian;
+
+///
+/// The metadata versions the command line reads
+///
+pub enum RequestedMetadata {
+    /// No metadata is read, e.g for `cordl schema`
+    None,
+    /// Every metadata file is this version
+    Version(MetadataVersion),
+    /// `cordl diff` between games with different metadata versions
+    Diff {
+        old: MetadataVersion,
+        new: MetadataVersion,
+    },
+}
+
+///
+/// The metadata versions the command line reads, from the header of its metadata files
+/// or `--metadata-version`
+///
+pub fn requested_metadata() -> color_eyre::Result<RequestedMetadata> {
+    let cli: Cli = Cli::parse();
+    let read_version = |metadata_path: &PathBuf| {
+        let mut header = vec![];
+        File::open(metadata_path)
+            .and_then(|file| file.take(8).read_to_end(&mut header))
+            .with_context(|| format!("il2cpp metadata not found {}", metadata_path.display()))?;
+        resolve_metadata_version(&header, cli.metadata_version)
+    };
+
+    let metadata_path = match &cli.command {
+        Some(Commands::Diff(diff_args)) => {
+            let old = read_version(&diff_args.old_metadata)?;
+            let new = read_version(&diff_args.new_metadata)?;
+
+            return Ok(match old == new {
+                true => RequestedMetadata::Version(old),
+                false => RequestedMetadata::Diff { old, new },
+            });
+        }
+        Some(Commands::Schema(_)) => None,
+        Some(Commands::Graph(graph_args)) => Some(&graph_args.metadata),
+        None => cli.metadata.as_ref(),
+    };
+
+    match metadata_path {
+        Some(metadata_path) => Ok(RequestedMetadata::Version(read_version(metadata_path)?)),
+        None => Ok(RequestedMetadata::None),
+    }
+}
+
+///
+/// Runs the command line, the metadata it reads must be the version this build of brocolib reads
+///
+pub fn run() -> color_eyre::Result<()> {
+    let mut cli: Cli = Cli::parse();
+    let profile = load_profile(&cli)?;
+
+    // flags take precedence over the profile
+    let output = &profile.output;
+    cli.out_dir = cli.out_dir.or_else(|| output.out_dir.clone());
+    cli.header_subdir = cli.header_subdir.or_else(|| output.header_subdir.clone());
+    cli.internals_dir = cli.internals_dir.or_else(|| output.internals_dir.clone());
+    cli.anonymous_namespace |= output.anonymous_namespace;
+    cli.crate_name = cli.crate_name.or_else(|| output.crate_name.clone());
+
+    if let Some(command) = &cli.command {
+        return match command {
+            Commands::Diff(diff_args) => run_diff(&cli, &profile, diff_args),
+            Commands::Schema(schema_args) => run_schema(schema_args),
+            Commands::Graph(graph_args) => run_graph(&cli, &profile, graph_args),
+        };
+    }
+
+    if !cli.format {
+        info!("Add --format/-f to format with clang-format at end")
+    }
+
+    println!(
+        "Running on {}",
+        Path::new("./").canonicalize().unwrap().display()
+    );
+
+    let (Some(metadata_path), Some(libil2cpp_path), Some(target)) =
+        (&cli.metadata, &cli.libil2cpp, cli.target)
+    else {
+        bail!("--metadata, --libil2cpp and a target are required");
+    };
+
+    with_cs_collection(
+        metadata_path,
+        libil2cpp_path,
+        &cli,
+        &profile,
+        |metadata, cs_context_collection| {
+            let generation_report = report::make_report(metadata, &cs_context_collection);
+            report::write_report(&generation_report, &cli.report, cli.format)?;
+
+            generate(&cli, target, metadata, cs_context_collection)
+        },
+    )
+}
+
+fn run_diff(
+    cli: &Cli,
+    profile: &GenerationProfile,
+    diff_args: &DiffArgs,
+) -> color_eyre::Result<()> {
+    #[cfg(feature = "json")]
+    {
+        use generate::json;
+
+        info!("Making old types");
+        let old_types = with_cs_collection(
+            &diff_args.old_metadata,
+            &diff_args.old_libil2cpp,
+            cli,
+            profile,
+            |metadata, collection| Ok(json::make_json_types(metadata, &collection, None)),
+        )?;
+        info!("Making new types");
+        let new_types = with_cs_collection(
+            &diff_args.new_metadata,
+            &diff_args.new_libil2cpp,
+            cli,
+            profile,
+            |metadata, collection| Ok(json::make_json_types(metadata, &collection, None)),
+        )?;
+
+        json::write_api_diff(
+            &old_types,
+            &new_types,
+            diff_args.output.as_deref(),
+            diff_args.json.as_deref(),
+            cli.format,
+        )
+    }
+
+    #[cfg(not(feature = "json"))]
+    {
+        let _ = (cli, profile, diff_args);
+        bail!("cordl diff requires the json feature")
+    }
+}
+
+///
+/// The old types of a `cordl diff` between metadata versions, serialized for `write_diff`.
+/// The old metadata must be the version this build of brocolib reads
+///
+pub fn make_old_diff_types() -> color_eyre::Result<Vec<u8>> {
+    let cli: Cli = Cli::parse();
+    let Some(Commands::Diff(diff_args)) = &cli.command else {
+        bail!("Not running cordl diff");
+    };
+
+    info!("Making old types");
+    make_diff_types(&cli, &diff_args.old_metadata, &diff_args.old_libil2cpp)
+}
+
+///
+/// The new types of a `cordl diff` between metadata versions, serialized for `write_diff`.
+/// The new metadata must be the version this build of brocolib reads
+///
+pub fn make_new_diff_types() -> color_eyre::Result<Vec<u8>> {
+    let cli: Cli = Cli::parse();
+    let Some(Commands::Diff(diff_args)) = &cli.command else {
+        bail!("Not running cordl diff");
+    };
+
+    info!("Making new types");
+    make_diff_types(&cli, &diff_args.new_metadata, &diff_args.new_libil2cpp)
+}
+
+fn make_diff_types(
+    cli: &Cli,
+    metadata_path: &Path,
+    libil2cpp_path: &Path,
+) -> color_eyre::Result<Vec<u8>> {
+    #[cfg(feature = "json")]
+    {
+        use generate::json;
+
+        let profile = load_profile(cli)?;
+        with_cs_collection(
+            metadata_path,
+            libil2cpp_path,
+            cli,
+            &profile,
+            |metadata, collection| json::make_json_types_data(metadata, &collection),
+        )
+    }
+
+    #[cfg(not(feature = "json"))]
+    {
+        let _ = (cli, metadata_path, libil2cpp_path);
+        bail!("cordl diff requires the json feature")
+    }
+}
+
+///
+/// Writes the report of a `cordl diff` between metadata versions,
+/// from the types `make_old_diff_types` and `make_new_diff_types` made in the build for each version
+///
+pub fn write_diff(old_types: &[u8], new_types: &[u8]) -> color_eyre::Result<()> {
+    let cli: Cli = Cli::parse();
+    let Some(Commands::Diff(diff_args)) = &cli.command else {
+        bail!("Not running cordl diff");
+    };
+
+    #[cfg(feature = "json")]
+    {
+        generate::json::write_api_diff_data(
+            old_types,
+            new_types,
+            diff_args.output.as_deref(),
+            diff_args.json.as_deref(),
+            cli.format,
+        )
+    }
+
+    #[cfg(not(feature = "json"))]
+    {
+        let _ = (old_types, new_types, diff_args);
+        bail!("cordl diff requires the json feature")
+    }
+}
+
+fn run_schema(schema_args: &SchemaArgs) -> color_eyre::Result<()> {
+    #[cfg(feature = "json")]
+    {
+        use generate::json;
+
+        match &schema_args.validate {
+            Some(path) => json::validate_json(path),
+            None => json::write_schemas(&schema_args.output),
+        }
+    }
+
+    #[cfg(not(feature = "json"))]
+    {
+        let _ = schema_args;
+        bail!("cordl schema requires the json feature")
+    }
+}
+
+fn run_graph(
+    cli: &Cli,
+    profile: &GenerationProfile,
+    graph_args: &GraphArgs,
+) -> color_eyre::Result<()> {
+    with_cs_collection(
+        &graph_args.metadata,
+        &graph_args.libil2cpp,
+        cli,
+        profile,
+        |metadata, collection| {
+            let roots = match_types(metadata, &graph_args.root, "--root");
+            let options = graph_main::GraphOptions {
+                format: graph_args.graph_format,
+                edges: graph_args.edges.clone(),
+                depth: graph_args.depth,
+                cluster_namespaces: graph_args.cluster_namespaces,
+            };
+
+            graph_main::run_graph(
+                &collection,
+                metadata,
+                &roots,
+                &options,
+                graph_args.output.as_deref(),
+            )
+        },
+    )
+}
+
+fn load_profile(cli: &Cli) -> color_eyre::Result<GenerationProfile> {
+    match &cli.profile {
+        Some(profile_path) => {
+            info!("Using profile {}", profile_path.display());
+            GenerationProfile::load(profile_path)
+        }
+        None => GenerationProfile::parse(DEFAULT_PROFILE),
+    }
+}
+
+fn parse_pointer_size(s: &str) -> Result<PointerSize, String> {
+    match s {
+        "4" => Ok(PointerSize::Bytes4),
+        "8" => Ok(PointerSize::Bytes8),
+        _ => Err(format!("{s} is not a pointer size, expected 4 or 8")),
+    }
+}
+
+///
+/// The version of the metadata, `version_override` takes precedence over the header
+///
+fn resolve_metadata_version(
+    global_metadata_data: &[u8],
+    version_override: Option<u32>,
+) -> color_eyre::Result<MetadataVersion> {
+    let detected = read_metadata_version(global_metadata_data);
+    let version = match (version_override, detected) {
+        (Some(version), Some(detected)) if version != detected => {
+            warn!("Metadata header reports version {detected}, using {version} instead");
+            version
+        }
+        (Some(version), _) => version,
+        (None, Some(detected)) => detected,
+        (None, None) => bail!(
+            "Unable to read the metadata version, the metadata may be encrypted. Use --metadata-version to override"
+        ),
+    };
+
+    MetadataVersion::from_number(version).ok_or_else(|| {
+        eyre!(
+            "Metadata version {version} is not supported, cordl reads versions {}",
+            MetadataVersion::ALL
+                .iter()
+                .map(|v| v.number())
+                .join(" and ")
+        )
+    })
+}
+
+///
+/// Ensures the metadata is the version brocolib was built for in this copy of cordl
+///
+fn check_metadata_version(
+    global_metadata_data: &[u8],
+    version_override: Option<u32>,
+) -> color_eyre::Result<()> {
+    let version = resolve_metadata_version(global_metadata_data, version_override)?;
+    info!("il2cpp metadata version {}", version.number());
+
+    if version != SUPPORTED_METADATA_VERSION {
+        bail!(
+            "Version {} metadata was passed to the v{} reader, cordl picks the reader from the version before parsing",
+            version.number(),
+            SUPPORTED_METADATA_VERSION.number()
+        );
+    }
+
+    Ok(())
+}
+
+///
+/// Parses the metadata and libil2cpp pair, then makes and fills every C# type
+/// `f` is called with the filled collection, since it borrows the parsed metadata
+///
+fn with_cs_collection<R>(
+    metadata_path: &Path,
+    libil2cpp_path: &Path,
+    cli: &Cli,
+    profile: &GenerationProfile,
+    f: impl FnOnce(&CordlMetadata, TypeContextCollection) -> color_eyre::Result<R>,
+) -> color_eyre::Result<R> {
+    let global_metadata_data = fs::read(metadata_path)
+        .with_context(|| format!("il2cpp metadata not found {}", metadata_path.display()))?;
+    let elf_data = fs::read(libil2cpp_path).with_context(|| {
+        format!(
+            "libil2cpp.so shared object not found {}",
+            libil2cpp_path.display()
+        )
+    })?;
+    check_metadata_version(&global_metadata_data, cli.metadata_version)?;
+
+    let pointer_size = match cli.pointer_size {
+        Some(pointer_size) => pointer_size,
+        None => PointerSize::from_elf(&elf_data).unwrap_or_else(|| {
+            warn!("Unable to detect the pointer size, assuming 64 bit");
+            PointerSize::Bytes8
+        }),
+    };
+    info!("Using {} byte pointers", pointer_size as u8);
+
+    let il2cpp_metadata = brocolib::Metadata::parse(&global_metadata_data, &elf_data)?;
+
+    let mut metadata = CordlMetadata {
+        metadata: &il2cpp_metadata,
+        code_registration: &il2cpp_metadata.runtime_metadata.code_registration,
+        metadata_registration: &il2cpp_metadata.runtime_metadata.metadata_registration,
+        method_calculations: Default::default(),
+        parent_to_child_map: Default::default(),
+        child_to_parent_map: Default::default(),
+
+        // found by parse
+        unity_object_tdi: TypeDefinitionIndex::new(u32::MAX),
+        object_tdi: TypeDefinitionIndex::new(u32::MAX),
+        string_tdi: TypeDefinitionIndex::new(u32::MAX),
+
+        name_to_tdi: Default::default(),
+        full_name_to_tdi: Default::default(),
+        field_default_value_indices: Default::default(),
+        parameter_default_value_indices: Default::default(),
+        custom_attribute_ranges: Default::default(),
+        blacklisted_types: Default::default(),
+        failed_types: Default::default(),
+        unmatched_blacklist_patterns: Default::default(),
+        pointer_size,
+        // For most il2cpp versions
+        packing_field_offset: 7,
+        size_is_default_offset: 12,
+        specified_packing_field_offset: 13,
+        packing_is_default_offset: 11,
+    };
+    let t = time::Instant::now();
+    info!("Parsing metadata methods");
+    metadata.parse();
+    info!("Finished in {}ms", t.elapsed().as_millis());
+
+    // blacklist types
+    {
+        let unmatched = profile.blacklist.apply(&mut metadata)?;
+        if !unmatched.is_empty() {
+            warn!(
+                "{} blacklist patterns did not match any type: {}",
+                unmatched.len(),
+                unmatched.iter().map(|(_, p)| p).join(", ")
+            );
+        }
+        metadata.unmatched_blacklist_patterns = unmatched;
+    }
+
+    // types which fail are blacklisted and everything is made again,
+    // so their dependents resolve them as blacklisted instead of half made
+    let cs_context_collection = loop {
+        let (cs_context_collection, failures) = make_cs_types(&metadata, cli);
+        if failures.is_empty() {
+            break cs_context_collection;
+        }
+
+        blacklist_failed_types(&mut metadata, failures)?;
+    };
+    report_failed_types(&metadata);
+
+    f(&metadata, cs_context_collection)
+}
+
+/// Makes and fills the C# types, returning the types which failed
+fn make_cs_types(metadata: &CordlMetadata, cli: &Cli) -> (TypeContextCollection, TypeFailures) {
+    let mut cs_context_collection = TypeContextCollection::new();
+    let mut failures = TypeFailures::new();
+
+    {
+        // First, make all the contexts
+        info!("Making types");
+        // roots are made first so the nested types can be added to their contexts
+        let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+        let (root_tdis, nested_tdis): (Vec<_>, Vec<_>) =
+            type_defs.iter().enumerate().partition_map(|(i, td)| {
+                let tdi = TypeDefinitionIndex::new(i as u32);
+                match td.declaring_type_index == u32::MAX {
+                    true => Either::Left(tdi),
+                    false => Either::Right(tdi),
+                }
+            });
+
+        failures.extend(cs_context_collection.make_all_from(metadata, &root_tdis));
+
+        info!("Making nested types");
+        failures.extend(cs_context_collection.make_all_nested_from(metadata, &nested_tdis));
+    }
+
+    // {
+    //     let total = metadata.metadata_registration.generic_method_table.len() as f64;
+    //     info!("Making generic type instantiations");
+    //     for (i, generic_class) in metadata
+    //         .metadata_registration
+    //         .generic_method_table
+    //         .iter()
+    //         .enumerate()
+    //     {
+    //         trace!(
+    //             "Making generic type instantiations {:.4}% ({i}/{total})",
+    //             (i as f64 / total * 100.0)
+    //         );
+    //         let method_spec = metadata
+    //             .metadata_registration
+    //             .method_specs
+    //             .get(generic_class.generic_method_index as usize)
+    //             .unwrap();
+
+    //         cpp_context_collection.make_generic_from(method_spec, &mut metadata, &STATIC_CONFIG);
+    //     }
+    // }
+    // {
+    //     let total = metadata.metadata_registration.generic_method_table.len() as f64;
+    //     info!("Filling generic types!");
+    //     for (i, generic_class) in metadata
+    //         .metadata_registration
+    //         .generic_method_table
+    //         .iter()
+    //         .enumerate()
+    //     {
+    //         trace!(
+    //             "Filling generic type instantiations {:.4}% ({i}/{total})",
+    //             (i as f64 / total * 100.0)
+    //         );
+    //         let method_spec = metadata
+    //             .metadata_registration
+    //             .method_specs
+    //             .get(generic_class.generic_method_index as usize)
+    //             .unwrap();
+
+    //         cpp_context_collection.fill_generic_class_inst(
+    //             method_spec,
+    //             &mut metadata,
+    //
+    //         );
+    //     }
+    // }
+
+    if cli.gen_generic_methods_specializations {
+        let total = metadata.metadata_registration.generic_method_table.len() as f64;
+        info!("Filling generic methods!");
+        for (i, generic_class) in metadata
+            .metadata_registration
+            .generic_method_table
+            .iter()
+            .enumerate()
+        {
+            trace!(
+                "Filling generic method instantiations {:.4}% ({i}/{total})",
+                (i as f64 / total * 100.0)
+            );
+            let method_spec = metadata
+                .metadata_registration
+                .method_specs
+                .get(generic_class.generic_method_index as usize)
+                .unwrap();
+
+            if let Err(e) = cs_context_collection.fill_generic_method_inst(method_spec, metadata) {
+                let method =
+                    &metadata.metadata.global_metadata.methods[method_spec.method_definition_index];
+                failures.push((method.declaring_type.into(), e));
+            }
+        }
+    }
+
+    info!("Registering handlers!");
+    // il2cpp_internals::register_il2cpp_types(&mut metadata)?;
+
+    // TODO: uncomment
+    // unity::register_unity(&mut metadata)?;
+    // object::register_system(&mut metadata)?;
+    // value_type::register_value_type(&mut metadata)?;
+    info!("Handlers registered!");
+
+    // Fill them now
+    info!("Filling types");
+    failures.extend(cs_context_collection.fill_all(metadata));
+
+    (cs_context_collection, failures)
+}
+
+fn blacklist_failed_types(
+    metadata: &mut CordlMetadata,
+    failures: TypeFailures,
+) -> color_eyre::Result<()> {
+    warn!(
+        "{} types failed to generate, blacklisting them and trying again",
+        failures.len()
+    );
+
+    let mut newly_blacklisted = HashSet::new();
+    for (tag, e) in failures {
+        let tdi = tag.get_tdi();
+        if newly_blacklisted.contains(&tdi) {
+            continue;
+        }
+
+        // blacklisted types are skipped, so failing again means the error is not in the type itself
+        if !metadata.blacklisted_types.insert(tdi) {
+            return Err(e.wrap_err(format!(
+                "{tag:?} failed to generate even though it is blacklisted"
+            )));
+        }
+        newly_blacklisted.insert(tdi);
+        metadata.failed_types.insert(tdi, format!("{e:#}"));
+    }
+
+    Ok(())
+}
+
+fn report_failed_types(metadata: &CordlMetadata) {
+    if metadata.failed_types.is_empty() {
+        return;
+    }
+
+    warn!(
+        "{} types failed to generate and were blacklisted:",
+        metadata.failed_types.len()
+    );
+    for (tdi, error) in metadata
+        .failed_types
+        .iter()
+        .sorted_by_key(|(tdi, _)| tdi.index())
+    {
+        let td = &metadata.metadata.global_metadata.type_definitions[*tdi];
+        warn!("  {}: {error}", td.full_name(metadata.metadata, true));
+    }
+}
+
+fn report_stats(cli: &Cli, stats: OutputStats) {
+    match cli.stats {
+        true => println!("Output: {stats}"),
+        false => info!("Output: {stats}"),
+    }
+}
+
+///
+/// The type definitions whose full name matches one of the glob `patterns`,
+/// types without a namespace also match as `GlobalNamespace.Name`.
+/// Patterns without `*` or `?` are looked up in `CordlMetadata::full_name_to_tdi`
+///
+fn match_types(metadata: &CordlMetadata, patterns: &[String], flag: &str) -> Vec<CsTypeTag> {
+    let is_glob = |pattern: &str| pattern.contains(['*', '?']);
+    let mut matched = vec![false; patterns.len()];
+    let mut tdis = vec![];
+
+    // exact names are looked up, only globs need every full name
+    for (i, pattern) in patterns.iter().enumerate() {
+        if is_glob(pattern) {
+            continue;
+        }
+
+        let global_tdi = pattern
+            .strip_prefix("GlobalNamespace.")
+            .and_then(|name| metadata.get_tdi(name))
+            .filter(|tdi| {
+                let td = &metadata.metadata.global_metadata.type_definitions[*tdi];
+                td.namespace(metadata.metadata).is_empty()
+            });
+        if let Some(tdi) = metadata.get_tdi(pattern).or(global_tdi) {
+            matched[i] = true;
+            tdis.push(tdi);
+        }
+    }
+
+    if patterns.iter().any(|p| is_glob(p)) {
+        let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+        for (tdi, td) in type_defs.iter().enumerate() {
+            let full_name = td.full_name(metadata.metadata, false);
+            let global_name = format!("GlobalNamespace.{full_name}");
+            for (i, pattern) in patterns.iter().enumerate() {
+                if is_glob(pattern)
+                    && (glob_match(pattern, &full_name)
+                        || (td.namespace(metadata.metadata).is_empty()
+                            && glob_match(pattern, &global_name)))
+                {
+                    matched[i] = true;
+                    tdis.push(TypeDefinitionIndex::new(tdi as u32));
+                }
+            }
+        }
+    }
+
+    for (pattern, _) in patterns.iter().zip(matched).filter(|(_, m)| !m) {
+        warn!("{flag} pattern {pattern} did not match any type");
+    }
+    tdis.into_iter()
+        .sorted_by_key(|tdi| tdi.index())
+        .dedup()
+        .map(CsTypeTag::TypeDefinitionIndex)
+        .collect_vec()
+}
+
+fn generate(
+    cli: &Cli,
+    target: TargetLang,
+    metadata: &CordlMetadata,
+    cs_context_collection: TypeContextCollection,
+) -> color_eyre::Result<()> {
+    let only_contexts = match cli.only.is_empty() {
+        true => None,
+        false => {
+            let roots = match_types(metadata, &cli.only, "--only");
+            let closure = cs_context_collection.dependency_closure(roots);
+            info!(
+                "Generating {} of {} contexts",
+                closure.len(),
+                cs_context_collection.get().len()
+            );
+            Some(closure)
+        }
+    };
+
+    if cli.remove_verbose_comments {
+        // TODO: uncomment
+        // remove_coments(&mut cpp_context_collection)?;
+    }
+
+    match target {
+        #[cfg(feature = "cpp")]
+        TargetLang::Cpp => {
+            use generate::cpp;
+
+            let config = cpp::config::CppGenerationConfig::new(
+                cli.out_dir.as_deref().unwrap_or(Path::new("./codegen")),
+                cli.header_subdir.as_deref().unwrap_or(Path::new("include")),
+                cli.internals_dir
+                    .as_deref()
+                    .unwrap_or(Path::new("cordl_internals")),
+                cli.anonymous_namespace,
+            );
+            let stats = cpp::cpp_main::run_cpp(
+                cs_context_collection,
+                metadata,
+                only_contexts.as_ref(),
+                &config,
+                cli.format,
+            )?;
+            report_stats(cli, stats);
+            Ok(())
+        }
+        #[cfg(feature = "json")]
+        TargetLang::SingleJSON => {
+            use generate::json;
+
+            let json = cli
+                .out_dir
+                .as_deref()
+                .unwrap_or(Path::new("."))
+                .join("cordl.json");
+            println!("Writing json file {json:?}");
+            json::make_json(
+                metadata,
+                &cs_context_collection,
+                only_contexts.as_ref(),
+                &json,
+                cli.format,
+            )?;
+            Ok(())
+        }
+        #[cfg(feature = "json")]
+        TargetLang::MultiJSON => {
+            use generate::json;
+
+            let json_folder = cli.out_dir.as_deref().unwrap_or(Path::new("./multi_json"));
+
+            println!("Writing json file {json_folder:?}");
+            json::make_json_folder(
+                metadata,
+                &cs_context_collection,
+                only_contexts.as_ref(),
+                json_folder,
+            )?;
+            Ok(())
+        }
+
+        #[cfg(feature = "rust")]
+        TargetLang::Rust => {
+            use generate::rust;
+
+            let config = rust::config::RustGenerationConfig::new(
+                cli.out_dir.as_deref().unwrap_or(Path::new("./codegen-rs")),
+                cli.crate_name.as_deref().unwrap_or("bs_cordl"),
+            );
+            let stats = rust::rust_main::run_rust(
+                cs_context_collection,
+                metadata,
+                only_contexts.as_ref(),
+                &config,
+            )?;
+            report_stats(cli, stats);
+
+            Ok(())
+        }
+
+        #[cfg(feature = "symbols")]
+        TargetLang::Symbols => {
+            use generate::symbols;
+
+            let symbols_folder = cli.out_dir.as_deref().unwrap_or(Path::new("./symbols"));
+
+            println!("Writing symbols to {symbols_folder:?}");
+            symbols::symbols_main::run_symbols(
+                &cs_context_collection,
+                metadata,
+                only_contexts.as_ref(),
+                symbols_folder,
+                cli.format,
+            )?;
+            Ok(())
+        }
+        #[cfg(feature = "csharp")]
+        TargetLang::CSharp => {
+            use generate::csharp;
+
+            let csharp_folder = cli.out_dir.as_deref().unwrap_or(Path::new("./csharp"));
+
+            println!("Writing C# declarations to {csharp_folder:?}");
+            let stats = csharp::csharp_main::run_csharp(
+                &cs_context_collection,
+                metadata,
+                only_contexts.as_ref(),
+                csharp_folder,
+                cli.split_namespaces,
+            )?;
+            report_stats(cli, stats);
+            Ok(())
+        }
+        #[cfg(feature = "c_header")]
+        TargetLang::CHeader => {
+            use generate::c_header;
+
+            let header_folder = cli.out_dir.as_deref().unwrap_or(Path::new("./c_header"));
+
+            println!("Writing C header to {header_folder:?}");
+            let stats = c_header::c_header_main::run_c_header(
+                &cs_context_collection,
+                metadata,
+                only_contexts.as_ref(),
+                header_folder,
+            )?;
+            report_stats(cli, stats);
+            Ok(())
+        }
+        #[cfg(feature = "frida")]
+        TargetLang::Frida => {
+            use generate::frida;
+
+            let frida_folder = cli.out_dir.as_deref().unwrap_or(Path::new("./frida"));
+
+            println!("Writing Frida typings to {frida_folder:?}");
+            let stats = frida::frida_main::run_frida(
+                &cs_context_collection,
+                metadata,
+                only_contexts.as_ref(),
+                frida_folder,
+            )?;
+            report_stats(cli, stats);
+            Ok(())
+        }
+        #[cfg(feature = "docs")]
+        TargetLang::Docs => {
+            use generate::docs;
+
+            let docs_folder = cli.out_dir.as_deref().unwrap_or(Path::new("./docs"));
+
+            println!("Writing documentation to {docs_folder:?}");
+            let stats = docs::docs_main::run_docs(
+                &cs_context_collection,
+                metadata,
+                only_contexts.as_ref(),
+                docs_folder,
+            )?;
+            report_stats(cli, stats);
+            Ok(())
+        }
+        _ => color_eyre::Result::<()>::Ok(()),
+    }
+}
@@ -1,2 +1,3 @@
 pub mod name_components;
+pub mod profile;
 pub mod type_resolver;
@@ -0,0 +1,287 @@
+use std::path::{Path, PathBuf};
+
+use brocolib::global_metadata::TypeDefinitionIndex;
+use color_eyre::eyre::{bail, Context};
+use log::{info, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::generate::metadata::CordlMetadata;
+
+/// Profile used when `--profile` is not specified
+pub const DEFAULT_PROFILE: &str = include_str!("../../cordl_profiles/default.json");
+
+///
+/// Per-game generation settings, loaded from a `.json` or `.toml` file through `--profile`
+///
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationProfile {
+    pub blacklist: BlacklistProfile,
+    pub output: OutputProfile,
+}
+
+///
+/// Where the backends write to, the matching command line flags take precedence
+///
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputProfile {
+    /// `--out-dir`
+    pub out_dir: Option<PathBuf>,
+    /// `--header-subdir`
+    pub header_subdir: Option<PathBuf>,
+    /// `--internals-dir`
+    pub internals_dir: Option<PathBuf>,
+    /// `--anonymous-namespace`
+    pub anonymous_namespace: bool,
+    /// `--crate-name`
+    pub crate_name: Option<String>,
+}
+
+///
+/// Types to blacklist, matched against `Il2CppTypeDefinition::full_name`
+/// e.g `UnityEngine.InputSystem.Utilities.InputActionTrace::Enumerator`
+///
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlacklistProfile {
+    /// Exact full names
+    pub names: Vec<String>,
+    /// Full name contains the pattern
+    pub substrings: Vec<String>,
+    /// `*` matches any sequence, `?` matches a single character
+    pub globs: Vec<String>,
+    /// Regexes matched against the full name
+    pub regexes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlacklistPatternKind {
+    Name,
+    Substring,
+    Glob,
+    Regex,
+}
+
+impl GenerationProfile {
+    pub fn load(path: &Path) -> color_eyre::Result<GenerationProfile> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("profile not found {}", path.display()))?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") | None => Self::parse(&content)
+                .with_context(|| format!("Unable to parse profile {}", path.display())),
+            Some("toml") => Ok(toml::from_str(&content)
+                .with_context(|| format!("Unable to parse profile {}", path.display()))?),
+            Some(ext) => bail!("Unsupported profile format .{ext}, expected .json or .toml"),
+        }
+    }
+
+    pub fn parse(content: &str) -> color_eyre::Result<GenerationProfile> {
+        Ok(serde_json::from_str(content)?)
+    }
+}
+
+impl BlacklistProfile {
+    ///
+    /// Inserts all matching types into `CordlMetadata::blacklisted_types`
+    /// Returns the patterns that did not match any type
+    ///
+    pub fn apply(
+        &self,
+        metadata: &mut CordlMetadata,
+    ) -> color_eyre::Result<Vec<(BlacklistPatternKind, String)>> {
+        let patterns = self
+            .names
+            .iter()
+            .map(|p| (BlacklistPatternKind::Name, p))
+            .chain(
+                self.substrings
+                    .iter()
+                    .map(|p| (BlacklistPatternKind::Substring, p)),
+            )
+            .chain(self.globs.iter().map(|p| (BlacklistPatternKind::Glob, p)))
+            .chain(
+                self.regexes
+                    .iter()
+                    .map(|p| (BlacklistPatternKind::Regex, p)),
+            )
+            .map(|(kind, p)| -> color_eyre::Result<_> {
+                let regex = match kind {
+                    BlacklistPatternKind::Regex => Some(
+                        Regex::new(p).with_context(|| format!("Invalid blacklist regex {p}"))?,
+                    ),
+                    _ => None,
+                };
+                Ok((kind, p, regex))
+            })
+            .collect::<color_eyre::Result<Vec<_>>>()?;
+        let mut matched = vec![false; patterns.len()];
+
+        // exact names are looked up, the other patterns need every full name
+        for (i, (kind, pattern, _)) in patterns.iter().enumerate() {
+            if *kind == BlacklistPatternKind::Name
+                && let Some(tdi) = metadata.get_tdi(pattern)
+            {
+                info!("Blacklisted {pattern}");
+                matched[i] = true;
+                metadata.blacklisted_types.insert(tdi);
+            }
+        }
+
+        if patterns
+            .iter()
+            .any(|(kind, _, _)| *kind != BlacklistPatternKind::Name)
+        {
+            let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+            for (tdi, td) in type_defs.iter().enumerate() {
+                let full_name = td.full_name(metadata.metadata, false);
+
+                let mut blacklisted = false;
+                for (i, (kind, pattern, regex)) in patterns.iter().enumerate() {
+                    let is_match = match (kind, regex) {
+                        (_, Some(regex)) => regex.is_match(&full_name),
+                        (BlacklistPatternKind::Substring, _) => {
+                            full_name.contains(pattern.as_str())
+                        }
+                        (BlacklistPatternKind::Glob, _) => glob_match(pattern, &full_name),
+                        (BlacklistPatternKind::Name | BlacklistPatternKind::Regex, None) => false,
+                    };
+
+                    if is_match {
+                        matched[i] = true;
+                        blacklisted = true;
+                    }
+                }
+
+                if blacklisted {
+                    info!("Blacklisted {full_name}");
+                    metadata
+                        .blacklisted_types
+                        .insert(TypeDefinitionIndex::new(tdi as u32));
+                }
+            }
+        }
+
+        let unmatched = patterns
+            .into_iter()
+            .zip(matched)
+            .filter(|(_, matched)| !matched)
+            .map(|((kind, pattern, _), _)| {
+                warn!("Unable to blacklist {kind:?} pattern {pattern}, no type matched");
+                (kind, pattern.clone())
+            })
+            .collect();
+
+        Ok(unmatched)
+    }
+}
+
+/// Simple glob matching, `*` matches any sequence and `?` matches a single character
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    let (mut p, mut t) = (0, 0);
+    // position of last `*` in pattern and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // let `*` consume one more character
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p + 1;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_star() {
+        assert!(glob_match(
+            "*Descriptor",
+            "UnityEngine.XR.XRInputSubsystemDescriptor"
+        ));
+        assert!(glob_match(
+            "UnityEngine.*Descriptor",
+            "UnityEngine.XR.Descriptor"
+        ));
+        assert!(glob_match("UnityEngine.XR.*", "UnityEngine.XR.InputDevice"));
+        assert!(glob_match("UnityEngine.XR.*", "UnityEngine.XR."));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*.*.*", "A.B.C"));
+
+        assert!(!glob_match("*Descriptor", "UnityEngine.XR.DescriptorList"));
+        assert!(!glob_match("UnityEngine.*Descriptor", "System.Descriptor"));
+        assert!(!glob_match("UnityEngine.XR.*", "UnityEngine.XRInput"));
+    }
+
+    #[test]
+    fn glob_question_mark() {
+        assert!(glob_match("?ist`1", "List`1"));
+        assert!(glob_match("List`?", "List`1"));
+        assert!(glob_match("Dictionary`?::Ent?y", "Dictionary`2::Entry"));
+
+        assert!(!glob_match("?ist`1", "ist`1"));
+        assert!(!glob_match("List`?", "List`12"));
+        assert!(!glob_match("Ent?y", "Enty"));
+    }
+
+    #[test]
+    fn glob_mixed() {
+        assert!(glob_match(
+            "*`?::<>c*",
+            "RpcHandler`1::<>c__DisplayClass3_0"
+        ));
+        assert!(glob_match("?*?", "ab"));
+        assert!(!glob_match("?*?", "a"));
+        assert!(glob_match("a*b?c*", "a__b_c"));
+        assert!(!glob_match("a*b?c*", "a__bc"));
+    }
+
+    #[test]
+    fn toml_profile() {
+        let toml_profile: GenerationProfile = toml::from_str(
+            r#"
+            [blacklist]
+            names = ["Foo.Bar"]
+            globs = ["UnityEngine.XR.*Descriptor"]
+
+            [output]
+            out_dir = "out"
+            anonymous_namespace = true
+            "#,
+        )
+        .unwrap();
+        let json_profile = GenerationProfile::parse(
+            r#"{
+                "blacklist": { "names": ["Foo.Bar"], "globs": ["UnityEngine.XR.*Descriptor"] },
+                "output": { "out_dir": "out", "anonymous_namespace": true }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(format!("{toml_profile:?}"), format!("{json_profile:?}"));
+    }
+}
@@ -3,13 +3,15 @@ use brocolib::{
     runtime_metadata::{Il2CppType, Il2CppTypeEnum, TypeData},
 };
 
+use color_eyre::eyre::{bail, ensure, eyre};
 use itertools::Itertools;
 use log::warn;
 use serde::{Deserialize, Serialize};
 
 use crate::generate::{
-    cs_context_collection::TypeContextCollection, cs_type::CsType, cs_type_tag::CsTypeTag,
-    metadata::CordlMetadata, type_extensions::ParameterDefinitionExtensions,
+    cs_context_collection::TypeContextCollection, cs_members::CsParamFlags, cs_type::CsType,
+    cs_type_tag::CsTypeTag, metadata::CordlMetadata,
+    type_extensions::ParameterDefinitionExtensions,
 };
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
@@ -59,7 +61,7 @@ impl TypeResolver<'_, '_> {
         to_resolve_idx: usize,
         typ_usage: TypeUsage,
         add_include: bool,
-    ) -> ResolvedType {
+    ) -> color_eyre::Result<ResolvedType> {
         let to_resolve = &self.cordl_metadata.metadata_registration.types[to_resolve_idx];
         let data = self.resolve_type_recurse(
             declaring_cs_type,
@@ -67,12 +69,12 @@ impl TypeResolver<'_, '_> {
             to_resolve_idx,
             typ_usage,
             add_include,
-        );
+        )?;
 
-        ResolvedType {
+        Ok(ResolvedType {
             data,
             ty: to_resolve_idx,
-        }
+        })
     }
 
     /// [declaring_generic_inst_types] the generic instantiation of the declaring type
@@ -83,7 +85,7 @@ impl TypeResolver<'_, '_> {
         to_resolve_idx: usize,
         typ_usage: TypeUsage,
         add_include: bool,
-    ) -> ResolvedTypeData {
+    ) -> color_eyre::Result<ResolvedTypeData> {
         let typ_tag = to_resolve.data;
         let metadata = self.cordl_metadata;
 
@@ -110,7 +112,7 @@ impl TypeResolver<'_, '_> {
                     .add_dependency_tag(CsTypeTag::from_type_data(
                         to_resolve.data,
                         self.cordl_metadata.metadata,
-                    ));
+                    )?);
                  ResolvedTypeData::Primitive(to_resolve.ty)
             }
             Il2CppTypeEnum::Class
@@ -118,7 +120,7 @@ impl TypeResolver<'_, '_> {
             | Il2CppTypeEnum::Typedbyref
             // ptr types
             | Il2CppTypeEnum::I
-            | Il2CppTypeEnum::U => self.resolve_ptr(typ_tag, declaring_cs_type, to_resolve, add_include),
+            | Il2CppTypeEnum::U => self.resolve_ptr(typ_tag, declaring_cs_type, to_resolve, add_include)?,
 
             // Single dimension array
             Il2CppTypeEnum::Szarray => {
@@ -130,10 +132,10 @@ impl TypeResolver<'_, '_> {
                             e,
                             typ_usage,
                             add_include
-                        )
+                        )?
                     }
 
-                    _ => panic!("Unknown type data for array {to_resolve:?}!"),
+                    _ => bail!("Unknown type data for array {to_resolve:?}!"),
                 };
 
                 ResolvedTypeData::Array(Box::new(generic))
@@ -151,19 +153,19 @@ impl TypeResolver<'_, '_> {
                         &metadata.metadata.global_metadata.generic_parameters[index];
 
                     let owner = generic_param.owner(metadata.metadata);
-                    assert!(owner.is_method != u32::MAX);
+                    ensure!(owner.is_method != u32::MAX, "Method generic {to_resolve:?} is not owned by a method!");
 
                     let (_gen_idx, gen_param) = owner
                         .generic_parameters(metadata.metadata)
                         .iter()
                         .find_position(|&p| p.name_index == generic_param.name_index)
-                        .unwrap();
+                        .ok_or_else(|| eyre!("Method generic {to_resolve:?} not found in its owner!"))?;
 
                                             let method_index = MethodIndex::new(owner.owner_index);
 
                         ResolvedTypeData::GenericMethodArg(method_index, index, gen_param.num)
                 }
-                _ => todo!(),
+                _ => bail!("Unknown type data for method generic {to_resolve:?}!"),
             },
             Il2CppTypeEnum::Var => match to_resolve.data {
                 // Il2CppMetadataGenericParameterHandle
@@ -175,26 +177,30 @@ impl TypeResolver<'_, '_> {
 
                     ResolvedTypeData::GenericArg(index, generic_param.num)
                 }
-                _ => todo!(),
+                _ => bail!("Unknown type data for generic {to_resolve:?}!"),
             },
             Il2CppTypeEnum::Genericinst => match to_resolve.data {
                 TypeData::GenericClassIndex(e) => {
                     let mr = &metadata.metadata_registration;
-                    let generic_class = mr.generic_classes.get(e).unwrap();
-                    let generic_inst = mr
-                        .generic_insts
-                        .get(generic_class.context.class_inst_idx.unwrap())
-                        .unwrap();
+                    let generic_class = mr
+                        .generic_classes
+                        .get(e)
+                        .ok_or_else(|| eyre!("No generic class {e} for {to_resolve:?}!"))?;
+                    let generic_inst = generic_class
+                        .context
+                        .class_inst_idx
+                        .and_then(|i| mr.generic_insts.get(i))
+                        .ok_or_else(|| eyre!("No generic inst for {to_resolve:?}!"))?;
 
                     let new_generic_inst_types = &generic_inst.types;
 
                     let generic_type_def = &mr.types[generic_class.type_index];
                     let TypeData::TypeDefinitionIndex(tdi) = generic_type_def.data else {
-                        panic!()
+                        bail!("Generic inst {to_resolve:?} is not of a type definition!")
                     };
 
                     if add_include {
-                        let generic_tag = CsTypeTag::from_type_data(to_resolve.data, metadata.metadata);
+                        let generic_tag = CsTypeTag::from_type_data(to_resolve.data, metadata.metadata)?;
 
                         // depend on both tdi and generic instantiation
                         declaring_cs_type.requirements.add_dependency_tag(tdi.into());
@@ -214,10 +220,10 @@ impl TypeResolver<'_, '_> {
                                 *gen_arg_t_idx,
                                 TypeUsage::GenericArg,
                                 should_include
-                            );
-                            (t, should_include)
+                            )?;
+                            Ok((t, should_include))
                         })
-                        .collect_vec();
+                        .collect::<color_eyre::Result<Vec<_>>>()?;
 
 
                     let generic_resolved_type = self.resolve_type(
@@ -225,13 +231,13 @@ impl TypeResolver<'_, '_> {
                         generic_class.type_index,
                         typ_usage,
                         add_include
-                    );
+                    )?;
 
                     // add generics to type def
                     ResolvedTypeData::GenericInst(Box::new(generic_resolved_type), generic_resolved_args)
                 }
 
-                _ => panic!("Unknown type data for generic inst {to_resolve:?}!"),
+                _ => bail!("Unknown type data for generic inst {to_resolve:?}!"),
             },
 
 
@@ -243,15 +249,15 @@ impl TypeResolver<'_, '_> {
                             e,
                             typ_usage,
                             add_include
-                        )
+                        )?
                     }
 
-                    _ => panic!("Unknown type data for array {to_resolve:?}!"),
+                    _ => bail!("Unknown type data for pointer {to_resolve:?}!"),
                 };
 
                 ResolvedTypeData::Ptr(Box::new(ptr_type))
             }
-            _ => panic!("/* UNKNOWN TYPE! {to_resolve:?} */"),
+            _ => bail!("Unknown type {to_resolve:?}!"),
         };
 
         let byref_allowed = matches!(
@@ -262,23 +268,20 @@ impl TypeResolver<'_, '_> {
                 | TypeUsage::GenericArg
         );
 
-        if (to_resolve.is_param_out() || (to_resolve.byref && !to_resolve.valuetype))
-            && byref_allowed
-        {
-            return ResolvedTypeData::ByRef(Box::new(ResolvedType {
+        if to_resolve.byref && byref_allowed {
+            let inner = Box::new(ResolvedType {
                 ty: to_resolve_idx,
                 data: ret,
-            }));
-        }
+            });
 
-        if to_resolve.is_param_in() && byref_allowed {
-            return ResolvedTypeData::ByRefConst(Box::new(ResolvedType {
-                ty: to_resolve_idx,
-                data: ret,
-            }));
+            // `in` parameters are readonly references
+            return match to_resolve.param_flags().contains(CsParamFlags::IN) {
+                true => Ok(ResolvedTypeData::ByRefConst(inner)),
+                false => Ok(ResolvedTypeData::ByRef(inner)),
+            };
         }
 
-        ret
+        Ok(ret)
     }
 
     fn resolve_ptr(
@@ -287,13 +290,16 @@ impl TypeResolver<'_, '_> {
         declaring_cs_type: &mut CsType,
         to_resolve: &Il2CppType,
         add_include: bool,
-    ) -> ResolvedTypeData {
+    ) -> color_eyre::Result<ResolvedTypeData> {
         let metadata = self.cordl_metadata;
         let ctx_collection = self.collection;
-        let typ_cpp_tag: CsTypeTag = typ_tag.into();
+        let TypeData::TypeDefinitionIndex(typ_tdi) = typ_tag else {
+            bail!("Unknown type data for class {to_resolve:?}!")
+        };
+        let typ_cpp_tag = CsTypeTag::TypeDefinitionIndex(typ_tdi);
         // Self
         if typ_cpp_tag == declaring_cs_type.self_tag {
-            return ResolvedTypeData::Type(typ_cpp_tag);
+            return Ok(ResolvedTypeData::Type(typ_cpp_tag));
         }
 
         if let TypeData::TypeDefinitionIndex(tdi) = to_resolve.data
@@ -301,7 +307,7 @@ impl TypeResolver<'_, '_> {
         {
             // blacklist if needed
 
-            return ResolvedTypeData::Blacklisted(typ_cpp_tag);
+            return Ok(ResolvedTypeData::Blacklisted(typ_cpp_tag));
         }
 
         if add_include {
@@ -311,10 +317,10 @@ impl TypeResolver<'_, '_> {
         }
 
         let to_incl_cpp_ty = ctx_collection
-            .get_cs_type(to_resolve.data.into())
-            .unwrap_or_else(|| panic!("Unable to get type to include {:?}", to_resolve.data));
+            .get_cs_type(typ_cpp_tag)
+            .ok_or_else(|| eyre!("Unable to get type to include {:?}", to_resolve.data))?;
 
-        ResolvedTypeData::Type(to_incl_cpp_ty.self_tag)
+        Ok(ResolvedTypeData::Type(to_incl_cpp_ty.self_tag))
     }
 }
 
@@ -322,4 +328,23 @@ impl ResolvedType {
     pub fn get_type<'a>(&self, metadata: &CordlMetadata<'a>) -> &'a Il2CppType {
         &metadata.metadata_registration.types[self.ty]
     }
+
+    /// All type tags referenced by this type, including generic args and element types
+    pub fn get_type_tags(&self) -> Vec<CsTypeTag> {
+        match &self.data {
+            ResolvedTypeData::Type(tag) | ResolvedTypeData::Blacklisted(tag) => vec![*tag],
+            ResolvedTypeData::Array(inner)
+            | ResolvedTypeData::Ptr(inner)
+            | ResolvedTypeData::ByRef(inner)
+            | ResolvedTypeData::ByRefConst(inner) => inner.get_type_tags(),
+            ResolvedTypeData::GenericInst(inner, args) => inner
+                .get_type_tags()
+                .into_iter()
+                .chain(args.iter().flat_map(|(arg, _)| arg.get_type_tags()))
+                .collect(),
+            ResolvedTypeData::GenericArg(_, _)
+            | ResolvedTypeData::GenericMethodArg(_, _, _)
+            | ResolvedTypeData::Primitive(_) => vec![],
+        }
+    }
 }
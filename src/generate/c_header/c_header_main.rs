@@ -0,0 +1,41 @@
+use std::{collections::HashSet, path::Path};
+
+use itertools::Itertools;
+use log::info;
+
+use crate::generate::{
+    cs_context_collection::TypeContextCollection,
+    cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata,
+    output::{OutputFiles, OutputStats},
+};
+
+use super::c_header_writer::CHeaderWriter;
+
+///
+/// Writes the layout of every type to `folder` as `il2cpp.h`, a C11 header
+/// which Ghidra's "Parse C Source" and IDA's "Load header" can import
+///
+pub fn run_c_header(
+    cs_collection: &TypeContextCollection,
+    metadata: &CordlMetadata,
+    only: Option<&HashSet<CsTypeTag>>,
+    folder: &Path,
+) -> color_eyre::Result<OutputStats> {
+    // generic type definitions have no layout, only their instantiations do
+    let types = cs_collection
+        .sorted_types(only)
+        .into_iter()
+        .filter(|t| t.generic_template.is_none() || t.generic_instantiations_args_types.is_some())
+        .collect_vec();
+
+    let file = folder.join("il2cpp.h");
+    info!("Writing {} types to {file:?}", types.len());
+    let writer = CHeaderWriter::new(metadata, cs_collection, &types);
+    let header = writer.write_header(&types)?;
+
+    let output = OutputFiles::new(folder)?;
+    output.write(&file, header.as_bytes())?;
+
+    output.finish()
+}
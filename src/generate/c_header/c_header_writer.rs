@@ -0,0 +1,512 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+};
+
+use brocolib::runtime_metadata::Il2CppTypeEnum;
+use itertools::Itertools;
+
+use crate::{
+    data::type_resolver::{ResolvedType, ResolvedTypeData},
+    generate::{
+        cs_context_collection::TypeContextCollection, cs_members::CsValue, cs_type::CsType,
+        cs_type_tag::CsTypeTag, metadata::CordlMetadata, type_extensions::TypeDefinitionExtensions,
+    },
+};
+
+/// Types every header starts with, so it parses without system headers
+const PRELUDE: &str = r#"typedef signed char int8_t;
+typedef unsigned char uint8_t;
+typedef short int16_t;
+typedef unsigned short uint16_t;
+typedef int int32_t;
+typedef unsigned int uint32_t;
+typedef long long int64_t;
+typedef unsigned long long uint64_t;
+"#;
+
+const IL2CPP_TYPES: &str = r#"typedef void (*Il2CppMethodPointer)();
+struct Il2CppClass;
+struct MethodInfo;
+
+typedef struct VirtualInvokeData {
+    Il2CppMethodPointer methodPtr;
+    const struct MethodInfo* method;
+} VirtualInvokeData;
+
+typedef struct Il2CppObject {
+    struct Il2CppClass* klass;
+    void* monitor;
+} Il2CppObject;
+
+typedef struct Il2CppArrayBounds {
+    uintptr_t length;
+    int32_t lower_bound;
+} Il2CppArrayBounds;
+
+typedef struct Il2CppArray {
+    Il2CppObject obj;
+    Il2CppArrayBounds* bounds;
+    uintptr_t max_length;
+} Il2CppArray;
+"#;
+
+/// A field placed at an offset of a struct
+struct CMember {
+    offset: u32,
+    size: u32,
+    declaration: String,
+}
+
+fn c_ident(name: &str) -> String {
+    let ident: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c,
+            false => '_',
+        })
+        .collect();
+
+    match ident.starts_with(|c: char| c.is_ascii_digit()) {
+        true => format!("_{ident}"),
+        false => ident,
+    }
+}
+
+fn primitive_c_type(ty: Il2CppTypeEnum) -> Option<&'static str> {
+    let c_type = match ty {
+        Il2CppTypeEnum::Boolean | Il2CppTypeEnum::U1 => "uint8_t",
+        Il2CppTypeEnum::I1 => "int8_t",
+        Il2CppTypeEnum::Char | Il2CppTypeEnum::U2 => "uint16_t",
+        Il2CppTypeEnum::I2 => "int16_t",
+        Il2CppTypeEnum::I4 => "int32_t",
+        Il2CppTypeEnum::U4 => "uint32_t",
+        Il2CppTypeEnum::I8 => "int64_t",
+        Il2CppTypeEnum::U8 => "uint64_t",
+        Il2CppTypeEnum::R4 => "float",
+        Il2CppTypeEnum::R8 => "double",
+        Il2CppTypeEnum::I => "intptr_t",
+        Il2CppTypeEnum::U => "uintptr_t",
+        Il2CppTypeEnum::String | Il2CppTypeEnum::Object => "Il2CppObject*",
+        _ => return None,
+    };
+    Some(c_type)
+}
+
+fn enum_value(value: &CsValue) -> Option<i64> {
+    match value {
+        CsValue::U8(x) => Some(*x as i64),
+        CsValue::U16(x) => Some(*x as i64),
+        CsValue::U32(x) => Some(*x as i64),
+        CsValue::U64(x) => i64::try_from(*x).ok(),
+        CsValue::I8(x) => Some(*x as i64),
+        CsValue::I16(x) => Some(*x as i64),
+        CsValue::I32(x) => Some(*x as i64),
+        CsValue::I64(x) => Some(*x),
+        _ => None,
+    }
+}
+
+///
+/// Writes the instance layout of types as C structs, with explicit padding so
+/// offsets match il2cpp regardless of how the parser aligns fields
+///
+pub struct CHeaderWriter<'a, 'b> {
+    pub metadata: &'a CordlMetadata<'b>,
+    pub collection: &'a TypeContextCollection,
+
+    names: HashMap<CsTypeTag, String>,
+    emitted: HashSet<CsTypeTag>,
+    in_progress: HashSet<CsTypeTag>,
+    out: String,
+}
+
+impl<'a, 'b> CHeaderWriter<'a, 'b> {
+    pub fn new(
+        metadata: &'a CordlMetadata<'b>,
+        collection: &'a TypeContextCollection,
+        types: &[&CsType],
+    ) -> Self {
+        // names are made unique in full name order so they are stable between runs
+        let mut used: HashMap<String, usize> = HashMap::new();
+        let names = types
+            .iter()
+            .map(|ty| {
+                let name = c_ident(&ty.cs_name_components.combine_all());
+                let count = used.entry(name.clone()).or_default();
+                *count += 1;
+
+                let name = match *count {
+                    1 => name,
+                    n => format!("{name}_{n}"),
+                };
+                (ty.self_tag, name)
+            })
+            .collect();
+
+        Self {
+            metadata,
+            collection,
+            names,
+            emitted: Default::default(),
+            in_progress: Default::default(),
+            out: String::new(),
+        }
+    }
+
+    fn is_generic_template(ty: &CsType) -> bool {
+        ty.generic_template.is_some() && ty.generic_instantiations_args_types.is_none()
+    }
+
+    /// The type written by this header for `ty`, `None` if it's not one
+    fn resolve_cs_type(&self, ty: &ResolvedType) -> Option<&'a CsType> {
+        let tag = match &ty.data {
+            ResolvedTypeData::Type(tag) => *tag,
+            ResolvedTypeData::GenericInst(_, _) => {
+                let il2cpp_ty = self.metadata.metadata_registration.types.get(ty.ty)?;
+                CsTypeTag::from_type_data(il2cpp_ty.data, self.metadata.metadata).ok()?
+            }
+            _ => return None,
+        };
+
+        if !self.names.contains_key(&tag) {
+            return None;
+        }
+        self.collection.get_cs_type(tag)
+    }
+
+    fn is_value_type_def(&self, ty: &ResolvedType) -> bool {
+        let tag = match &ty.data {
+            ResolvedTypeData::Type(tag) | ResolvedTypeData::Blacklisted(tag) => *tag,
+            ResolvedTypeData::GenericInst(inner, _) => return self.is_value_type_def(inner),
+            _ => return false,
+        };
+        let td = &self.metadata.metadata.global_metadata.type_definitions[tag.get_tdi()];
+
+        td.is_value_type() || td.is_enum_type()
+    }
+
+    ///
+    /// C type of a field and the array suffix of its declarator.
+    /// Types that can't be spelled, such as unresolved value types, become byte arrays of their size
+    ///
+    fn c_type(&self, ty: &ResolvedType, size: usize) -> (String, String) {
+        let bytes = || ("uint8_t".to_string(), format!("[{size}]"));
+
+        match &ty.data {
+            ResolvedTypeData::Primitive(p) => primitive_c_type(*p)
+                .map(|t| (t.to_string(), String::new()))
+                .unwrap_or_else(bytes),
+            ResolvedTypeData::Array(_) => ("Il2CppArray*".to_string(), String::new()),
+            ResolvedTypeData::Ptr(_)
+            | ResolvedTypeData::ByRef(_)
+            | ResolvedTypeData::ByRefConst(_) => ("void*".to_string(), String::new()),
+            ResolvedTypeData::GenericArg(_, _) | ResolvedTypeData::GenericMethodArg(_, _, _) => {
+                bytes()
+            }
+            _ => match self.resolve_cs_type(ty) {
+                Some(cs_type) if cs_type.is_enum_type => {
+                    (self.names[&cs_type.self_tag].clone(), String::new())
+                }
+                // value types can only be embedded once defined
+                Some(cs_type) if cs_type.is_value_type => {
+                    match self.emitted.contains(&cs_type.self_tag) && cs_type.size_info.is_some() {
+                        true => (
+                            format!("struct {}", self.names[&cs_type.self_tag]),
+                            String::new(),
+                        ),
+                        false => bytes(),
+                    }
+                }
+                Some(cs_type) => (
+                    format!("struct {}_o*", self.names[&cs_type.self_tag]),
+                    String::new(),
+                ),
+                _ if self.is_value_type_def(ty) => bytes(),
+                _ => ("Il2CppObject*".to_string(), String::new()),
+            },
+        }
+    }
+
+    /// Writes `members` sorted by offset, padding gaps and up to `size`.
+    /// Overlapping members (explicit layout) are kept as comments
+    fn write_members(&mut self, mut members: Vec<CMember>, size: u32) -> std::fmt::Result {
+        members.sort_by_key(|m| m.offset);
+
+        let mut end = 0;
+        for member in members {
+            if member.offset < end {
+                writeln!(
+                    self.out,
+                    "    // overlaps: {} // 0x{:X}",
+                    member.declaration, member.offset
+                )?;
+                continue;
+            }
+            if member.offset > end {
+                writeln!(
+                    self.out,
+                    "    uint8_t _pad_{end:X}[0x{:X}];",
+                    member.offset - end
+                )?;
+            }
+            writeln!(
+                self.out,
+                "    {}; // 0x{:X}",
+                member.declaration, member.offset
+            )?;
+            end = member.offset + member.size;
+        }
+
+        if end < size {
+            writeln!(self.out, "    uint8_t _pad_{end:X}[0x{:X}];", size - end)?;
+        }
+        Ok(())
+    }
+
+    fn instance_members(&self, ty: &CsType) -> Vec<CMember> {
+        ty.fields
+            .iter()
+            .filter(|f| f.instance && !f.is_const)
+            .filter_map(|f| {
+                let (c_type, suffix) = self.c_type(&f.field_ty, f.size);
+                Some(CMember {
+                    offset: f.offset?,
+                    size: f.size as u32,
+                    declaration: format!("{c_type} {}{suffix}", c_ident(&f.name)),
+                })
+            })
+            .collect()
+    }
+
+    ///
+    /// Writes the struct of `ty` after the value types it embeds and its parent
+    ///
+    pub fn write_type(&mut self, ty: &'a CsType) -> std::fmt::Result {
+        if self.emitted.contains(&ty.self_tag) || !self.in_progress.insert(ty.self_tag) {
+            return Ok(());
+        }
+
+        // reference types are only pointed to, so only embedded value types and the parent are needed
+        let parent = ty
+            .parent
+            .as_ref()
+            .filter(|_| !ty.is_value_type && !ty.is_enum_type)
+            .and_then(|p| self.resolve_cs_type(p));
+        let dependencies = ty
+            .fields
+            .iter()
+            .filter(|f| f.instance && !f.is_const)
+            .filter_map(|f| self.resolve_cs_type(&f.field_ty))
+            .filter(|t| t.is_value_type && !t.is_enum_type)
+            .chain(parent)
+            .collect_vec();
+        for dependency in dependencies {
+            self.write_type(dependency)?;
+        }
+
+        self.in_progress.remove(&ty.self_tag);
+        self.emitted.insert(ty.self_tag);
+
+        let name = self.names[&ty.self_tag].clone();
+        if ty.is_enum_type {
+            writeln!(self.out, "// {}", ty.cs_name_components.combine_all())?;
+            return self.write_enum(ty, &name);
+        }
+        let Some(size_info) = ty.size_info.as_ref() else {
+            return Ok(());
+        };
+
+        writeln!(self.out, "// {}", ty.cs_name_components.combine_all())?;
+
+        let members = self.instance_members(ty);
+        if ty.is_value_type {
+            writeln!(self.out, "struct {name} {{")?;
+            self.write_members(members, size_info.instance_size)?;
+        } else {
+            let base = ty
+                .parent
+                .as_ref()
+                .and_then(|p| self.resolve_cs_type(p))
+                .filter(|p| self.emitted.contains(&p.self_tag) && p.size_info.is_some());
+            let (base_decl, base_size) = match base {
+                Some(parent) => (
+                    format!("struct {}_o base", self.names[&parent.self_tag]),
+                    parent.size_info.as_ref().unwrap().instance_size,
+                ),
+                None => (
+                    "Il2CppObject obj".to_string(),
+                    self.metadata.object_size() as u32,
+                ),
+            };
+
+            writeln!(self.out, "struct {name}_o {{")?;
+            let base_member = CMember {
+                offset: 0,
+                size: base_size,
+                declaration: base_decl,
+            };
+            self.write_members(
+                std::iter::once(base_member).chain(members).collect(),
+                size_info.instance_size,
+            )?;
+        }
+        writeln!(self.out, "}};")?;
+        writeln!(self.out)
+    }
+
+    fn write_enum(&mut self, ty: &CsType, name: &str) -> std::fmt::Result {
+        let backing = ty
+            .enum_backing_type
+            .and_then(primitive_c_type)
+            .unwrap_or("int32_t");
+        writeln!(self.out, "typedef {backing} {name};")?;
+
+        // C enumerators must fit in an int
+        let values = ty
+            .fields
+            .iter()
+            .filter(|f| f.is_const)
+            .filter_map(|f| Some((f, enum_value(f.value.as_ref()?)?)))
+            .filter(|(_, v)| i32::try_from(*v).is_ok())
+            .collect_vec();
+        if !values.is_empty() {
+            writeln!(self.out, "enum {name}_Values {{")?;
+            for (field, value) in values {
+                writeln!(self.out, "    {name}_{} = {value},", c_ident(&field.name))?;
+            }
+            writeln!(self.out, "}};")?;
+        }
+        writeln!(self.out)
+    }
+
+    ///
+    /// Writes the static fields at their offsets from the field offsets table.
+    /// Generic instantiations have no table entries, il2cpp lays their static fields out at runtime
+    /// in declaration order with natural alignment, which is mirrored here.
+    /// Thread static fields are stored per thread and left out
+    ///
+    pub fn write_static_fields(&mut self, ty: &CsType) -> std::fmt::Result {
+        let fields = ty
+            .fields
+            .iter()
+            .filter(|f| !f.instance && !f.is_const)
+            .filter(|f| {
+                !f.attributes
+                    .iter()
+                    .any(|a| a.name == "System.ThreadStaticAttribute")
+            })
+            .collect_vec();
+        if fields.is_empty() || Self::is_generic_template(ty) {
+            return Ok(());
+        }
+
+        let pointer_size = self.metadata.pointer_size as u32;
+        let mut natural_offset = 0;
+        let members = fields
+            .into_iter()
+            .filter_map(|f| {
+                let offset = match ty.generic_instantiations_args_types {
+                    Some(_) => {
+                        let alignment = (f.size as u32).clamp(1, pointer_size).next_power_of_two();
+                        let offset = natural_offset.next_multiple_of(alignment);
+                        natural_offset = offset + f.size as u32;
+                        offset
+                    }
+                    None => f.static_offset?,
+                };
+
+                let (c_type, suffix) = self.c_type(&f.field_ty, f.size);
+                Some(CMember {
+                    offset,
+                    size: f.size as u32,
+                    declaration: format!("{c_type} {}{suffix}", c_ident(&f.name)),
+                })
+            })
+            .collect_vec();
+        if members.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(
+            self.out,
+            "struct {}_StaticFields {{",
+            self.names[&ty.self_tag]
+        )?;
+        self.write_members(members, 0)?;
+        writeln!(self.out, "}};")?;
+        writeln!(self.out)
+    }
+
+    ///
+    /// Writes the vtable entries of the methods declared by `ty`,
+    /// slots of inherited methods it does not override are left unnamed
+    ///
+    pub fn write_vtable(&mut self, ty: &CsType) -> std::fmt::Result {
+        if ty.is_interface || Self::is_generic_template(ty) {
+            return Ok(());
+        }
+
+        let slots: BTreeMap<u16, String> = ty
+            .methods
+            .iter()
+            .filter_map(|m| Some((m.method_data.slot?, c_ident(&m.name))))
+            .collect();
+        let Some(last_slot) = slots.keys().last().copied() else {
+            return Ok(());
+        };
+
+        writeln!(self.out, "struct {}_VTable {{", self.names[&ty.self_tag])?;
+        for slot in 0..=last_slot {
+            match slots.get(&slot) {
+                Some(name) => writeln!(self.out, "    VirtualInvokeData _{slot}_{name};")?,
+                None => writeln!(self.out, "    VirtualInvokeData _{slot};")?,
+            }
+        }
+        writeln!(self.out, "}};")?;
+        writeln!(self.out)
+    }
+
+    ///
+    /// Writes the header for `types`, which must be the types passed to `new`
+    ///
+    pub fn write_header(mut self, types: &[&'a CsType]) -> Result<String, std::fmt::Error> {
+        let pointer_types = match self.metadata.pointer_size as u8 {
+            4 => "typedef int32_t intptr_t;\ntypedef uint32_t uintptr_t;\n",
+            _ => "typedef int64_t intptr_t;\ntypedef uint64_t uintptr_t;\n",
+        };
+
+        writeln!(self.out, "// Generated by cordl")?;
+        writeln!(self.out, "#ifndef CORDL_IL2CPP_H")?;
+        writeln!(self.out, "#define CORDL_IL2CPP_H")?;
+        writeln!(self.out)?;
+        writeln!(self.out, "{PRELUDE}{pointer_types}")?;
+        writeln!(self.out, "#pragma pack(push, 1)")?;
+        writeln!(self.out)?;
+        writeln!(self.out, "{IL2CPP_TYPES}")?;
+
+        // reference types are only used through pointers, so can be declared up front
+        for ty in types.iter().filter(|t| !t.is_value_type && !t.is_enum_type) {
+            writeln!(self.out, "struct {}_o;", self.names[&ty.self_tag])?;
+        }
+        writeln!(self.out)?;
+
+        // enums first as the other types use them by name
+        for ty in types.iter().copied().filter(|t| t.is_enum_type) {
+            self.write_type(ty)?;
+        }
+        for ty in types.iter().copied().filter(|t| !t.is_interface) {
+            self.write_type(ty)?;
+        }
+        for ty in types {
+            self.write_static_fields(ty)?;
+            self.write_vtable(ty)?;
+        }
+
+        writeln!(self.out, "#pragma pack(pop)")?;
+        writeln!(self.out)?;
+        writeln!(self.out, "#endif")?;
+
+        Ok(self.out)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod c_header_main;
+
+mod c_header_writer;
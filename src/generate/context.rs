@@ -25,7 +25,11 @@ impl TypeContext {
     }
 
     // TODO: Move out, this is CSContext
-    pub fn make(metadata: &CordlMetadata, tdi: TypeDefinitionIndex, tag: CsTypeTag) -> TypeContext {
+    pub fn make(
+        metadata: &CordlMetadata,
+        tdi: TypeDefinitionIndex,
+        tag: CsTypeTag,
+    ) -> color_eyre::Result<TypeContext> {
         let t = &metadata.metadata.global_metadata.type_definitions[tdi];
 
         let components = t.get_name_components(metadata.metadata);
@@ -37,7 +41,7 @@ impl TypeContext {
             typedef_types: Default::default(),
         };
 
-        match CsType::make_cs_type(metadata, tdi, tag) {
+        match CsType::make_cs_type(metadata, tdi, tag)? {
             Some(cpptype) => {
                 x.insert_cs_type(cpptype);
             }
@@ -49,7 +53,7 @@ impl TypeContext {
             }
         }
 
-        x
+        Ok(x)
     }
 
     pub fn insert_cs_type(&mut self, cpp_type: CsType) {
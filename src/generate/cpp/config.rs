@@ -1,14 +1,4 @@
-use std::{path::PathBuf, sync::LazyLock};
-
-pub static STATIC_CONFIG: LazyLock<CppGenerationConfig> = LazyLock::new(|| CppGenerationConfig {
-    header_path: PathBuf::from("./codegen/include"),
-    source_path: PathBuf::from("./codegen/src"),
-    dst_internals_path: PathBuf::from("./codegen/include/cordl_internals"),
-    dst_header_internals_file: PathBuf::from(
-        "./codegen/include/cordl_internals/cordl_internals.hpp",
-    ),
-    use_anonymous_namespace: false,
-});
+use std::path::{Path, PathBuf};
 
 pub struct CppGenerationConfig {
     pub source_path: PathBuf,
@@ -19,6 +9,28 @@ pub struct CppGenerationConfig {
 }
 
 impl CppGenerationConfig {
+    ///
+    /// `header_subdir` is relative to `out_dir` and `internals_subdir` to the header folder,
+    /// so the internals stay includable from the generated headers
+    ///
+    pub fn new(
+        out_dir: &Path,
+        header_subdir: &Path,
+        internals_subdir: &Path,
+        use_anonymous_namespace: bool,
+    ) -> Self {
+        let header_path = out_dir.join(header_subdir);
+        let dst_internals_path = header_path.join(internals_subdir);
+
+        CppGenerationConfig {
+            source_path: out_dir.join("src"),
+            dst_header_internals_file: dst_internals_path.join("cordl_internals.hpp"),
+            header_path,
+            dst_internals_path,
+            use_anonymous_namespace,
+        }
+    }
+
     pub fn namespace_cpp(&self, string: &str) -> String {
         let final_ns = if string.is_empty() {
             "GlobalNamespace".to_owned()
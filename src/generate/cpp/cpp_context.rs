@@ -1,8 +1,7 @@
 use std::cmp::Ordering;
-use std::io::{BufWriter, Write};
+use std::io::Write;
 use std::{
     collections::{HashMap, HashSet},
-    fs::{create_dir_all, remove_file, File},
     path::PathBuf,
 };
 
@@ -13,12 +12,12 @@ use log::trace;
 use pathdiff::diff_paths;
 
 use crate::generate::context::TypeContext;
-use crate::generate::cpp::config::STATIC_CONFIG;
 use crate::generate::cpp::cpp_members::{CppForwardDeclare, CppInclude};
 use crate::generate::cpp::cpp_type::CORDL_NO_INCLUDE_IMPL_DEFINE;
 
 use crate::generate::cs_type_tag::CsTypeTag;
 use crate::generate::metadata::CordlMetadata;
+use crate::generate::output::OutputFiles;
 use crate::generate::type_extensions::TypeDefinitionExtensions;
 use crate::generate::writer::{Writable, Writer};
 use crate::helpers::sorting::DependencyGraph;
@@ -172,40 +171,27 @@ impl CppContext {
     }
 
     /// Writes the C++ type definitions and implementations to the appropriate files.
-    pub fn write(&self, config: &CppGenerationConfig) -> color_eyre::Result<()> {
-        // Write typedef file first
-        if self.typedef_path.exists() {
-            remove_file(self.typedef_path.as_path())?;
-        }
-        if !self
-            .typedef_path
-            .parent()
-            .context("parent is not a directory!")?
-            .is_dir()
-        {
-            // Assume it's never a file
-            create_dir_all(
-                self.typedef_path
-                    .parent()
-                    .context("Failed to create all directories!")?,
-            )?;
-        }
-
+    /// Files whose content did not change are left untouched
+    pub fn write(
+        &self,
+        config: &CppGenerationConfig,
+        output: &OutputFiles,
+    ) -> color_eyre::Result<()> {
         let base_path = &config.header_path;
 
         trace!("Writing {:?}", self.typedef_path.as_path());
         let mut typedef_writer = Writer {
-            stream: BufWriter::new(File::create(self.typedef_path.as_path())?),
+            stream: Vec::new(),
             indent: 0,
             newline: true,
         };
         let mut typeimpl_writer = Writer {
-            stream: BufWriter::new(File::create(self.type_impl_path.as_path())?),
+            stream: Vec::new(),
             indent: 0,
             newline: true,
         };
         let mut fundamental_writer = Writer {
-            stream: BufWriter::new(File::create(self.fundamental_path.as_path())?),
+            stream: Vec::new(),
             indent: 0,
             newline: true,
         };
@@ -234,7 +220,7 @@ impl CppContext {
         // this is so confusing but basically gets the relative folder
         // navigation for `_config.hpp`
         let dest_path = diff_paths(
-            &STATIC_CONFIG.dst_header_internals_file,
+            &config.dst_header_internals_file,
             self.typedef_path.parent().unwrap(),
         )
         .unwrap();
@@ -357,7 +343,7 @@ impl CppContext {
         )?;
 
         // anonymous namespace
-        if STATIC_CONFIG.use_anonymous_namespace {
+        if config.use_anonymous_namespace {
             writeln!(typedef_writer, "CORDL_MODULE_EXPORT_STRUCT namespace {{")?;
             writeln!(typeimpl_writer, "CORDL_MODULE_EXPORT_STRUCT namespace {{")?;
         }
@@ -435,7 +421,7 @@ impl CppContext {
         }
 
         // end anonymous namespace
-        if STATIC_CONFIG.use_anonymous_namespace {
+        if config.use_anonymous_namespace {
             writeln!(typedef_writer, "}} // end anonymous namespace")?;
             writeln!(typeimpl_writer, "}} // end anonymous namespace")?;
         }
@@ -459,6 +445,10 @@ impl CppContext {
             writeln!(fundamental_writer, "// IWYU pragma: end_exports")?;
         }
 
+        output.write(&self.typedef_path, &typedef_writer.stream)?;
+        output.write(&self.type_impl_path, &typeimpl_writer.stream)?;
+        output.write(&self.fundamental_path, &fundamental_writer.stream)?;
+
         Ok(())
     }
 
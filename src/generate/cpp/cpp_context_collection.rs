@@ -1,6 +1,5 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs::File,
     io::Write,
     path::{Path, PathBuf},
 };
@@ -14,8 +13,8 @@ use pathdiff::diff_paths;
 use rayon::iter::ParallelIterator;
 
 use crate::generate::{
-    cpp::config::STATIC_CONFIG, cs_context_collection::TypeContextCollection, cs_type::CsType,
-    cs_type_tag::CsTypeTag, metadata::CordlMetadata,
+    cs_context_collection::TypeContextCollection, cs_type::CsType, cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata, output::OutputFiles,
 };
 
 unsafe impl Send for CsTypeTag {}
@@ -84,6 +83,7 @@ impl CppContextCollection {
         let name_resolver = CppNameResolver {
             cordl_metadata: metadata,
             collection: self,
+            config,
         };
 
         cpp_type.fill(cs_type, &name_resolver, config);
@@ -209,12 +209,18 @@ impl CppContextCollection {
         &mut self.all_contexts
     }
 
-    pub fn write_all(&self, config: &CppGenerationConfig) -> color_eyre::Result<()> {
+    pub fn write_all(
+        &self,
+        config: &CppGenerationConfig,
+        output: &OutputFiles,
+    ) -> color_eyre::Result<()> {
         let amount = self.all_contexts.len() as f64;
         self.all_contexts
-            .iter()
+            .values()
+            .collect_vec()
+            .into_par_iter()
             .enumerate()
-            .try_for_each(|(i, (_, c))| {
+            .try_for_each(|(i, c)| {
                 trace!(
                     "Writing {:.4}% ({}/{}) {}",
                     (i as f64 / amount * 100.0),
@@ -222,17 +228,21 @@ impl CppContextCollection {
                     amount,
                     c.fundamental_path.display(),
                 );
-                c.write(config)
+                c.write(config, output)
             })
     }
 
-    pub fn write_namespace_headers(&self) -> color_eyre::Result<()> {
+    pub fn write_namespace_headers(
+        &self,
+        config: &CppGenerationConfig,
+        output: &OutputFiles,
+    ) -> color_eyre::Result<()> {
         self.all_contexts
             .iter()
             .into_group_map_by(|(_, c)| c.fundamental_path.parent())
             .into_iter()
             .try_for_each(|(dir, contexts)| -> color_eyre::Result<()> {
-                let namespace = if dir.unwrap() == STATIC_CONFIG.header_path {
+                let namespace = if dir.unwrap() == config.header_path {
                     "GlobalNamespace"
                 } else {
                     dir.unwrap().file_name().unwrap().to_str().unwrap()
@@ -254,7 +264,7 @@ impl CppContextCollection {
                     // add includes
                     .map(|(_, c)| {
                         let stripped_path =
-                            diff_paths(&c.fundamental_path, &STATIC_CONFIG.header_path).unwrap();
+                            diff_paths(&c.fundamental_path, &config.header_path).unwrap();
 
                         let stripped_path_friendly = if cfg!(windows) {
                             stripped_path.to_string_lossy().replace('\\', "/")
@@ -275,7 +285,7 @@ impl CppContextCollection {
                     contexts.len()
                 );
 
-                let mut file = File::create(path)?;
+                let mut file = Vec::new();
 
                 writeln!(
                     file,
@@ -295,6 +305,7 @@ impl CppContextCollection {
                     #endif
                 "
                 )?;
+                output.write(&path, &file)?;
 
                 Ok(())
             })?;
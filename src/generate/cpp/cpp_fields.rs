@@ -272,7 +272,8 @@ pub(crate) fn handle_const_fields(
                 // TODO: Make enum ctors inline defined
                 if f_type.valuetype && f_type.ty == Il2CppTypeEnum::Valuetype {
                     let field_cpp_tag: CsTypeTag =
-                        CsTypeTag::from_type_data(f_type.data, metadata.metadata);
+                        CsTypeTag::from_type_data(f_type.data, metadata.metadata)
+                            .expect("Value type field is not a type definition");
                     let field_cpp_td_tag: CsTypeTag = field_cpp_tag.get_tdi().into();
                     let field_cpp_type = name_resolver.collection.get_cpp_type(field_cpp_td_tag);
 
@@ -284,7 +285,10 @@ pub(crate) fn handle_const_fields(
 
                         cpp_type.requirements.add_impl_include(
                             field_cpp_type,
-                            CppInclude::new_context_typeimpl(field_cpp_context),
+                            CppInclude::new_context_typeimpl(
+                                field_cpp_context,
+                                name_resolver.config,
+                            ),
                         );
                     }
                 }
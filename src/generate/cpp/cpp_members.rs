@@ -11,12 +11,11 @@ use std::{
     fmt::Debug,
     hash::Hash,
     path::{Path, PathBuf},
-    rc::Rc,
     sync::Arc,
 };
 
 use super::{
-    config::STATIC_CONFIG,
+    config::CppGenerationConfig,
     cpp_context::CppContext,
     cpp_type::{CppType, CORDL_REFERENCE_TYPE_CONSTRAINT},
 };
@@ -100,8 +99,9 @@ impl CppLine {
     }
 }
 
-pub trait WritableDebug: Writable + Debug {}
-impl<T: Writable + Debug> WritableDebug for T {}
+// Send + Sync so contexts can be written from several threads
+pub trait WritableDebug: Writable + Debug + Send + Sync {}
+impl<T: Writable + Debug + Send + Sync> WritableDebug for T {}
 
 #[derive(Debug, Eq, Hash, PartialEq, Clone)]
 pub struct CppForwardDeclareGroup {
@@ -147,6 +147,7 @@ pub enum CppMember {
     MethodDecl(CppMethodDecl),
     MethodImpl(CppMethodImpl),
     Property(CppPropertyDecl),
+    Event(CppEventDecl),
     ConstructorDecl(CppConstructorDecl),
     ConstructorImpl(CppConstructorImpl),
     NestedStruct(CppNestedStruct),
@@ -244,6 +245,18 @@ pub struct CppPropertyDecl {
     pub brief_comment: Option<String>,
 }
 
+/// A C# event, exposed as a property returning an `EventW` that supports `+=` and `-=`
+#[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CppEventDecl {
+    pub cpp_name: String,
+    /// The delegate type
+    pub event_ty: String,
+    pub instance: bool,
+    pub add: Option<String>,
+    pub remove: Option<String>,
+    pub brief_comment: Option<String>,
+}
+
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CppParam {
     pub name: String,
@@ -569,7 +582,7 @@ impl PartialOrd for CppConstructorImpl {
 pub struct CppNestedStruct {
     pub declaring_name: String,
     pub base_type: Option<String>,
-    pub declarations: Vec<Rc<CppMember>>,
+    pub declarations: Vec<Arc<CppMember>>,
     pub is_enum: bool,
     pub is_class: bool,
     pub is_private: bool,
@@ -579,7 +592,7 @@ pub struct CppNestedStruct {
 
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub struct CppNestedUnion {
-    pub declarations: Vec<Rc<CppMember>>,
+    pub declarations: Vec<Arc<CppMember>>,
     pub brief_comment: Option<String>,
     pub offset: u32,
     pub is_private: bool,
@@ -660,22 +673,21 @@ impl CppParam {
 }
 
 impl CppInclude {
-    // smelly use of config but whatever
-    pub fn new_context_typedef(context: &CppContext) -> Self {
+    pub fn new_context_typedef(context: &CppContext, config: &CppGenerationConfig) -> Self {
         Self {
-            include: diff_paths(&context.typedef_path, &STATIC_CONFIG.header_path).unwrap(),
+            include: diff_paths(&context.typedef_path, &config.header_path).unwrap(),
             system: false,
         }
     }
-    pub fn new_context_typeimpl(context: &CppContext) -> Self {
+    pub fn new_context_typeimpl(context: &CppContext, config: &CppGenerationConfig) -> Self {
         Self {
-            include: diff_paths(&context.type_impl_path, &STATIC_CONFIG.header_path).unwrap(),
+            include: diff_paths(&context.type_impl_path, &config.header_path).unwrap(),
             system: false,
         }
     }
-    pub fn new_context_fundamental(context: &CppContext) -> Self {
+    pub fn new_context_fundamental(context: &CppContext, config: &CppGenerationConfig) -> Self {
         Self {
-            include: diff_paths(&context.fundamental_path, &STATIC_CONFIG.header_path).unwrap(),
+            include: diff_paths(&context.fundamental_path, &config.header_path).unwrap(),
             system: false,
         }
     }
@@ -4,8 +4,8 @@ use std::io::Write;
 use crate::generate::writer::{SortLevel, Sortable, Writable, Writer};
 
 use super::cpp_members::{
-    CppCommentedString, CppConstructorDecl, CppConstructorImpl, CppFieldDecl, CppFieldImpl,
-    CppForwardDeclare, CppInclude, CppLine, CppMember, CppMethodDecl, CppMethodImpl,
+    CppCommentedString, CppConstructorDecl, CppConstructorImpl, CppEventDecl, CppFieldDecl,
+    CppFieldImpl, CppForwardDeclare, CppInclude, CppLine, CppMember, CppMethodDecl, CppMethodImpl,
     CppMethodSizeStruct, CppNestedStruct, CppNestedUnion, CppNonMember, CppParam, CppPropertyDecl,
     CppStaticAssert, CppTemplate, CppUsingAlias,
 };
@@ -596,6 +596,51 @@ impl Sortable for CppPropertyDecl {
     }
 }
 
+impl Writable for CppEventDecl {
+    fn write(&self, writer: &mut Writer) -> color_eyre::Result<()> {
+        let ty = &self.event_ty;
+        let identifier = &self.cpp_name;
+        let getter = format!("__cordl_event_{identifier}");
+        let static_prefix = match self.instance {
+            true => "",
+            false => "static ",
+        };
+
+        // accessors that do not exist become no-ops
+        let make_accessor = |accessor: &Option<String>| match (accessor, self.instance) {
+            (Some(m), true) => format!("[this]({ty} d) {{ this->{m}(d); }}"),
+            (Some(m), false) => format!("[]({ty} d) {{ {m}(d); }}"),
+            (None, _) => format!("[]({ty}) {{}}"),
+        };
+        let add = make_accessor(&self.add);
+        let remove = make_accessor(&self.remove);
+
+        if let Some(comment) = &self.brief_comment {
+            writeln!(writer, "/// @brief {comment}")?;
+        }
+
+        writeln!(
+            writer,
+            "{static_prefix}inline ::cordl_internals::EventW<{ty}> {getter}() {{ return ::cordl_internals::EventW<{ty}>({add}, {remove}); }}"
+        )?;
+
+        // static members cannot be declspec properties, the getter is used instead
+        if self.instance {
+            writeln!(
+                writer,
+                "__declspec(property(get={getter})) ::cordl_internals::EventW<{ty}> {identifier};"
+            )?;
+        }
+
+        Ok(())
+    }
+}
+impl Sortable for CppEventDecl {
+    fn sort_level(&self) -> SortLevel {
+        SortLevel::Properties
+    }
+}
+
 impl Writable for CppMethodSizeStruct {
     fn write(&self, writer: &mut Writer) -> color_eyre::Result<()> {
         writeln!(
@@ -780,6 +825,7 @@ impl Writable for CppMember {
             CppMember::FieldImpl(f) => f.write(writer),
             CppMember::MethodDecl(m) => m.write(writer),
             CppMember::Property(p) => p.write(writer),
+            CppMember::Event(e) => e.write(writer),
             CppMember::Comment(c) => c.write(writer),
             CppMember::MethodImpl(i) => i.write(writer),
             CppMember::ConstructorDecl(c) => c.write(writer),
@@ -813,6 +859,7 @@ impl Sortable for CppMember {
             CppMember::MethodDecl(t) => t.sort_level(),
             CppMember::MethodImpl(t) => t.sort_level(),
             CppMember::Property(t) => t.sort_level(),
+            CppMember::Event(t) => t.sort_level(),
             CppMember::ConstructorDecl(t) => t.sort_level(),
             CppMember::ConstructorImpl(t) => t.sort_level(),
             CppMember::NestedStruct(t) => t.sort_level(),
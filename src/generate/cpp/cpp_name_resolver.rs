@@ -7,6 +7,7 @@ use crate::{
 };
 
 use super::{
+    config::CppGenerationConfig,
     cpp_context_collection::CppContextCollection,
     cpp_members::{CppForwardDeclare, CppInclude},
     cpp_name_components::CppNameComponents,
@@ -22,6 +23,7 @@ pub const IL2CPP_OBJECT_TYPE: &str = "Il2CppObject";
 pub struct CppNameResolver<'a, 'b> {
     pub cordl_metadata: &'a CordlMetadata<'b>,
     pub collection: &'a CppContextCollection,
+    pub config: &'a CppGenerationConfig,
 }
 
 impl<'b> CppNameResolver<'_, 'b> {
@@ -245,18 +247,18 @@ impl<'b> CppNameResolver<'_, 'b> {
                 true => {
                     declaring_cpp_type.requirements.add_def_include(
                         Some(incl_ty),
-                        CppInclude::new_context_typedef(incl_context),
+                        CppInclude::new_context_typedef(incl_context, self.config),
                     );
                     declaring_cpp_type.requirements.add_impl_include(
                         Some(incl_ty),
-                        CppInclude::new_context_typeimpl(incl_context),
+                        CppInclude::new_context_typeimpl(incl_context, self.config),
                     );
                 }
                 // add forward declare
                 false => {
                     declaring_cpp_type.requirements.add_forward_declare((
                         CppForwardDeclare::from_cpp_type(incl_ty),
-                        CppInclude::new_context_typedef(incl_context),
+                        CppInclude::new_context_typedef(incl_context, self.config),
                     ));
                 }
             }
@@ -1,6 +1,5 @@
 use std::{
     collections::{HashMap, HashSet},
-    rc::Rc,
     sync::Arc,
     usize,
 };
@@ -19,7 +18,7 @@ use crate::{
     generate::{
         cpp::cpp_members::{CppMethodSizeStruct, CppStaticAssert},
         cs_members::{
-            CSMethodFlags, CsConstructor, CsField, CsMethod, CsParam, CsProperty, CsValue,
+            CSMethodFlags, CsConstructor, CsEvent, CsField, CsMethod, CsParam, CsProperty, CsValue,
         },
         cs_type::CsType,
         cs_type_tag::CsTypeTag,
@@ -36,9 +35,10 @@ use super::{
     config::CppGenerationConfig,
     cpp_fields,
     cpp_members::{
-        CppConstructorDecl, CppConstructorImpl, CppFieldDecl, CppForwardDeclare, CppInclude,
-        CppLine, CppMember, CppMethodData, CppMethodDecl, CppMethodImpl, CppNestedStruct,
-        CppNonMember, CppParam, CppPropertyDecl, CppTemplate, CppUsingAlias, WritableDebug,
+        CppConstructorDecl, CppConstructorImpl, CppEventDecl, CppFieldDecl, CppForwardDeclare,
+        CppInclude, CppLine, CppMember, CppMethodData, CppMethodDecl, CppMethodImpl,
+        CppNestedStruct, CppNonMember, CppParam, CppPropertyDecl, CppTemplate, CppUsingAlias,
+        WritableDebug,
     },
     cpp_name_components::CppNameComponents,
     cpp_name_resolver::{CppNameResolver, VALUE_WRAPPER_TYPE},
@@ -455,7 +455,8 @@ impl CppType {
             let declaring_ty =
                 &metadata.metadata_registration.types[declaring_td.declaring_type_index as usize];
 
-            let declaring_tag = CsTypeTag::from_type_data(declaring_ty.data, metadata.metadata);
+            let declaring_tag = CsTypeTag::from_type_data(declaring_ty.data, metadata.metadata)
+                .expect("Declaring type is not a type definition");
 
             declaring_td = declaring_tag
                 .get_tdi()
@@ -508,6 +509,9 @@ impl CppType {
                     name_resolver,
                     config,
                 );
+                if cs_type.is_flags_enum() {
+                    self.create_enum_flag_operators();
+                }
             }
             self.add_default_ctor(false);
         } else if t.is_interface() {
@@ -527,6 +531,7 @@ impl CppType {
         self.make_fields(cs_type.fields, name_resolver, config);
         self.make_methods(cs_type.methods, name_resolver, config);
         self.make_properties(cs_type.properties, name_resolver, config);
+        self.make_events(cs_type.events, name_resolver, config);
         self.make_constructors(cs_type.constructors, name_resolver, config);
 
         self.make_parent(cs_type.parent, name_resolver);
@@ -607,8 +612,10 @@ impl CppType {
         CppParam {
             name: config.name_cpp(&p.name),
             ty: ty.combine_all(),
-            modifiers: "".to_string(), // TODO: Convert flags
-            def_value: p.def_value.as_ref().map(|v| v.to_string()),
+            // ref/in/out are already expressed through ByRef<T>/ByRefConst<T>
+            modifiers: "".to_string(),
+            // C# defaults such as enum or struct values are not always valid C++
+            def_value: None,
         }
     }
 
@@ -647,6 +654,31 @@ impl CppType {
         }
     }
 
+    fn make_events(
+        &mut self,
+        events: Vec<CsEvent>,
+        name_resolver: &CppNameResolver,
+        config: &CppGenerationConfig,
+    ) {
+        self.declarations.reserve(events.len());
+        for event in events {
+            let event_resolved_ty =
+                name_resolver.resolve_name(self, &event.event_ty, TypeUsage::Property, false);
+
+            let event_decl = CppEventDecl {
+                // field-like events have a backing field of the same name
+                cpp_name: config.name_cpp(&format!("event_{}", event.name)),
+                event_ty: event_resolved_ty.combine_all(),
+                instance: event.instance,
+                add: event.add.map(|a| config.name_cpp(&a.1)),
+                remove: event.remove.map(|r| config.name_cpp(&r.1)),
+                brief_comment: Some(format!("Event {}", event.name)),
+            };
+
+            self.declarations.push(CppMember::Event(event_decl).into());
+        }
+    }
+
     fn make_constructors(
         &mut self,
         constructors: Vec<CsConstructor>,
@@ -684,7 +716,8 @@ impl CppType {
 
         let parent_name = name_resolver.resolve_name(self, &parent, TypeUsage::TypeName, true);
 
-        let parent_tag = CsTypeTag::from_type_data(parent_ty.data, cordl_metadata.metadata);
+        let parent_tag = CsTypeTag::from_type_data(parent_ty.data, cordl_metadata.metadata)
+            .expect("Parent was resolved when making the C# type");
         let parent_tdi: TypeDefinitionIndex = parent_tag.into();
         let ctx_collection = name_resolver.collection;
 
@@ -700,7 +733,7 @@ impl CppType {
 
         self.requirements.add_impl_include(
             Some(base_type_cpp_type),
-            CppInclude::new_context_typeimpl(base_type_context),
+            CppInclude::new_context_typeimpl(base_type_context, name_resolver.config),
         );
 
         self.parent = Some(parent_name.remove_pointer().combine_all());
@@ -839,7 +872,7 @@ impl CppType {
                     nested.generic_instantiations_args_types.is_none(),
                 );
                 let fd = CppForwardDeclare::from_cpp_type(nested);
-                let inc = CppInclude::new_context_typedef(nested_context);
+                let inc = CppInclude::new_context_typedef(nested_context, config);
 
                 (alias, fd, inc)
             })
@@ -1391,6 +1424,7 @@ impl CppType {
                     // enums static fields are always the enum values
                     let f_name = field.name(metadata.metadata);
                     let value = CsType::field_default_value(metadata, field_index)
+                        .expect("Invalid enum value")
                         .expect("Enum without value!")
                         .to_string();
 
@@ -1406,7 +1440,7 @@ impl CppType {
             is_class: false,
             is_enum: true,
             is_private: false,
-            declarations: enum_entries.map(Rc::new).collect(),
+            declarations: enum_entries.map(Arc::new).collect(),
             brief_comment: Some(format!("Nested struct {unwrapped_name}")),
             packing: None,
         };
@@ -1449,6 +1483,83 @@ impl CppType {
             .push(CppMember::MethodDecl(backing_operator_decl).into());
     }
 
+    /// Bitwise operators and `has_flag` for `[System.Flags]` enums
+    fn create_enum_flag_operators(&mut self) {
+        let self_name = self.cpp_name().clone();
+
+        let method_decl =
+            |cpp_name: String, body: String, parameters: Vec<CppParam>| CppMethodDecl {
+                cpp_name,
+                instance: true,
+                return_type: self_name.clone(),
+
+                brief: None,
+                body: Some(vec![Arc::new(CppLine::make(body))]),
+                is_const: true,
+                is_constexpr: true,
+                is_virtual: false,
+                is_explicit_operator: false,
+                is_implicit_operator: false,
+                is_no_except: true,
+                parameters,
+                prefix_modifiers: vec![],
+                suffix_modifiers: vec![],
+                template: None,
+                is_inline: true,
+            };
+        let other_param = || CppParam {
+            name: "other".to_string(),
+            ty: self_name.clone(),
+            modifiers: "const&".to_string(),
+            def_value: None,
+        };
+
+        let mut methods = vec![];
+        for op in ["|", "&", "^"] {
+            let value = format!(
+                "static_cast<{__CORDL_BACKING_ENUM_TYPE}>(this->value__ {op} other.value__)"
+            );
+
+            methods.push(method_decl(
+                format!("operator{op}"),
+                format!("return {self_name}({value});"),
+                vec![other_param()],
+            ));
+            methods.push(CppMethodDecl {
+                return_type: format!("{self_name}&"),
+                is_const: false,
+                ..method_decl(
+                    format!("operator{op}="),
+                    format!("this->value__ = {value}; return *this;"),
+                    vec![other_param()],
+                )
+            });
+        }
+
+        methods.push(method_decl(
+            "operator~".to_string(),
+            format!(
+                "return {self_name}(static_cast<{__CORDL_BACKING_ENUM_TYPE}>(~this->value__));"
+            ),
+            vec![],
+        ));
+        methods.push(CppMethodDecl {
+            brief: Some("Whether all bits of flag are set".to_string()),
+            return_type: "bool".to_string(),
+            ..method_decl(
+                "has_flag".to_string(),
+                "return (this->value__ & flag.value__) == flag.value__;".to_string(),
+                vec![CppParam {
+                    name: "flag".to_string(),
+                    ..other_param()
+                }],
+            )
+        });
+
+        self.declarations
+            .extend(methods.into_iter().map(|m| CppMember::MethodDecl(m).into()));
+    }
+
     fn create_valuetype_field_wrapper(&mut self) {
         if self.size_info.is_none() {
             todo!("Why does this type not have a valid size??? {:?}", self);
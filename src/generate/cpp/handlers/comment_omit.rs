@@ -26,6 +26,9 @@ pub fn remove_coments(context_collection: &mut CppContextCollection) -> Result<(
                         CppMember::Property(cpp_property_decl) => {
                             cpp_property_decl.brief_comment = None;
                         }
+                        CppMember::Event(cpp_event_decl) => {
+                            cpp_event_decl.brief_comment = None;
+                        }
                         CppMember::MethodDecl(cpp_method_decl) => {
                             cpp_method_decl.brief = None;
                         }
@@ -1,6 +1,6 @@
 pub mod cpp_main;
 
-mod config;
+pub mod config;
 mod cpp_context;
 mod cpp_context_collection;
 mod cpp_fields;
@@ -5,8 +5,10 @@ use brocolib::{
     global_metadata::TypeDefinitionIndex,
     runtime_metadata::{Il2CppMethodSpec, TypeData},
 };
-use itertools::Itertools;
-use log::{info, warn};
+use color_eyre::eyre::{eyre, Report};
+use itertools::{Either, Itertools};
+use log::{info, trace, warn};
+use rayon::prelude::*;
 
 use crate::{data::type_resolver::TypeResolver, generate::cs_type::CsType};
 
@@ -17,6 +19,16 @@ use super::{
     type_extensions::TypeDefinitionExtensions,
 };
 
+/// A nested type made by `make_nested_type`, ready to be inserted into the collection
+enum NestedType {
+    /// Inherits its declaring type, so it is moved to its own context
+    Unnested(TypeContext),
+    Nested(CsType),
+}
+
+/// Types which failed to be made or filled, with the reason
+pub type TypeFailures = Vec<(CsTypeTag, Report)>;
+
 pub struct TypeContextCollection {
     // Should always be a TypeDefinitionIndex
     pub all_contexts: HashMap<CsTypeTag, TypeContext>,
@@ -27,11 +39,15 @@ pub struct TypeContextCollection {
 }
 
 impl TypeContextCollection {
-    fn fill_cpp_type(&mut self, cpp_type: &mut CsType, metadata: &CordlMetadata) {
+    fn fill_cpp_type(
+        &mut self,
+        cpp_type: &mut CsType,
+        metadata: &CordlMetadata,
+    ) -> color_eyre::Result<()> {
         let tag = cpp_type.self_tag;
 
         if self.filled_types.contains(&tag) {
-            return;
+            return Ok(());
         }
         if self.filling_types.contains(&tag) {
             panic!("Currently filling type {tag:?}, cannot fill")
@@ -44,17 +60,24 @@ impl TypeContextCollection {
             cordl_metadata: metadata,
             collection: self,
         };
-        cpp_type.fill_from_il2cpp(&type_resolver);
+        let result = cpp_type.fill_from_il2cpp(&type_resolver);
 
-        self.filled_types.insert(tag);
         self.filling_types.remove(&tag.clone());
+        result?;
+        self.filled_types.insert(tag);
+
+        Ok(())
     }
 
-    pub fn fill(&mut self, type_tag: CsTypeTag, metadata: &CordlMetadata) {
+    pub fn fill(
+        &mut self,
+        type_tag: CsTypeTag,
+        metadata: &CordlMetadata,
+    ) -> color_eyre::Result<()> {
         let context_tag = self.get_context_root_tag(type_tag);
 
         if self.filled_types.contains(&type_tag) {
-            return;
+            return Ok(());
         }
 
         if self.borrowing_types.contains(&context_tag) {
@@ -71,14 +94,70 @@ impl TypeContextCollection {
 
         // In some occasions, the CppContext can be empty
         if let Some((_t, mut cpp_type)) = cpp_type_entry {
-            self.fill_cpp_type(&mut cpp_type, metadata);
+            let result = self.fill_cpp_type(&mut cpp_type, metadata);
 
             // Move ownership back up
             self.all_contexts
                 .get_mut(&context_tag)
                 .expect("No cpp context")
                 .insert_cs_type(cpp_type);
+
+            result?;
         }
+
+        Ok(())
+    }
+
+    ///
+    /// Fills every type made so far in parallel.
+    /// Filling only looks up the tags of other types, so each type is filled
+    /// against the collection as it is and moved back in afterwards.
+    /// Types which fail to fill are left unfilled and returned
+    ///
+    pub fn fill_all(&mut self, metadata: &CordlMetadata) -> TypeFailures {
+        let unfilled = self
+            .all_contexts
+            .iter()
+            .flat_map(|(context_tag, context)| {
+                context
+                    .typedef_types
+                    .iter()
+                    .map(move |(tag, cs_type)| (*context_tag, tag, cs_type))
+            })
+            .filter(|(_, tag, _)| !self.filled_types.contains(tag))
+            .collect_vec();
+        let total = unfilled.len();
+
+        let (filled, failures): (Vec<_>, TypeFailures) = unfilled
+            .into_par_iter()
+            .enumerate()
+            .map(|(i, (context_tag, _, cs_type))| {
+                trace!(
+                    "Filling type {:.4}% ({i}/{total})",
+                    (i as f64 / total as f64 * 100.0)
+                );
+
+                let mut cs_type = cs_type.clone();
+                let type_resolver = TypeResolver {
+                    cordl_metadata: metadata,
+                    collection: self,
+                };
+                match cs_type.fill_from_il2cpp(&type_resolver) {
+                    Ok(()) => Either::Left((context_tag, cs_type)),
+                    Err(e) => Either::Right((cs_type.self_tag, e)),
+                }
+            })
+            .partition_map(|r| r);
+
+        for (context_tag, cs_type) in filled {
+            self.filled_types.insert(cs_type.self_tag);
+            self.all_contexts
+                .get_mut(&context_tag)
+                .expect("No cpp context")
+                .insert_cs_type(cs_type);
+        }
+
+        failures
     }
 
     ///
@@ -130,13 +209,66 @@ impl TypeContextCollection {
             .unwrap_or(ty)
     }
 
+    ///
+    /// Collects the context root tags of `roots` and every context they transitively
+    /// depend on through requirements, parents, interfaces and nested types.
+    /// Writing only these contexts yields a closed set of files
+    ///
+    pub fn dependency_closure(
+        &self,
+        roots: impl IntoIterator<Item = CsTypeTag>,
+    ) -> HashSet<CsTypeTag> {
+        let mut visited: HashSet<CsTypeTag> = HashSet::new();
+        let mut queue: Vec<CsTypeTag> = roots.into_iter().collect();
+
+        while let Some(tag) = queue.pop() {
+            let mut context_tag = self.get_context_root_tag(tag);
+            // generic instantiations that were never made live in their definition's context
+            if !self.all_contexts.contains_key(&context_tag) {
+                context_tag = self.get_context_root_tag(tag.get_tdi().into());
+            }
+
+            if !visited.insert(context_tag) {
+                continue;
+            }
+
+            let Some(context) = self.all_contexts.get(&context_tag) else {
+                continue;
+            };
+
+            for cs_type in context.typedef_types.values() {
+                queue.extend(cs_type.requirements.depending_types.iter().copied());
+                queue.extend(cs_type.nested_types.iter().copied());
+                queue.extend(cs_type.parent.iter().flat_map(|p| p.get_type_tags()));
+                queue.extend(cs_type.interfaces.iter().flat_map(|i| i.get_type_tags()));
+                if let Some(declaring_ty) = cs_type.declaring_ty {
+                    queue.push(declaring_ty);
+                }
+            }
+        }
+
+        visited
+    }
+
+    ///
+    /// The types of the contexts in `only`, or of every context, sorted by full name
+    /// so the output is the same between runs
+    ///
+    pub fn sorted_types(&self, only: Option<&HashSet<CsTypeTag>>) -> Vec<&CsType> {
+        self.all_contexts
+            .iter()
+            .filter(|(tag, _)| only.is_none_or(|only| only.contains(tag)))
+            .flat_map(|(_, c)| c.get_types().values())
+            .sorted_by_cached_key(|t| t.cs_name_components.combine_all())
+            .collect()
+    }
+
     pub fn make_nested_from(
         &mut self,
         metadata: &CordlMetadata<'_>,
         tdi: TypeDefinitionIndex,
-    ) -> Option<&mut TypeContext> {
+    ) -> color_eyre::Result<Option<&mut TypeContext>> {
         let ty_tag = CsTypeTag::TypeDefinitionIndex(tdi);
-        let ty_def = &metadata.metadata.global_metadata.type_definitions[tdi];
         let context_root_tag = self.get_context_root_tag(ty_tag);
 
         if self.filling_types.contains(&context_root_tag) {
@@ -146,13 +278,65 @@ impl TypeContextCollection {
         // Why is the borrow checker so dumb?
         // Using entries causes borrow checker to die :(
         if self.filled_types.contains(&ty_tag) {
-            return Some(self.all_contexts.get_mut(&context_root_tag).unwrap());
+            return Ok(Some(self.all_contexts.get_mut(&context_root_tag).unwrap()));
         }
 
         if self.get_cs_type(ty_tag).is_some() {
-            return self.get_context_mut(ty_tag);
+            return Ok(self.get_context_mut(ty_tag));
+        }
+
+        let Some(nested_type) = self.make_nested_type(metadata, tdi)? else {
+            return Ok(None);
+        };
+        Ok(self.insert_nested_type(ty_tag, nested_type))
+    }
+
+    ///
+    /// Makes the nested types `tdis` in parallel,
+    /// their declaring contexts must already be made.
+    /// Returns the types which failed to be made
+    ///
+    pub fn make_all_nested_from(
+        &mut self,
+        metadata: &CordlMetadata<'_>,
+        tdis: &[TypeDefinitionIndex],
+    ) -> TypeFailures {
+        let total = tdis.len();
+        let (nested_types, failures): (Vec<_>, TypeFailures) = tdis
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, tdi)| {
+                let ty_tag = CsTypeTag::TypeDefinitionIndex(*tdi);
+                if self.filled_types.contains(&ty_tag) || self.get_cs_type(ty_tag).is_some() {
+                    return None;
+                }
+
+                trace!(
+                    "Making nested types {:.4}% ({i}/{total})",
+                    (i as f64 / total as f64 * 100.0)
+                );
+                match self.make_nested_type(metadata, *tdi) {
+                    Ok(nested_type) => Some(Either::Left((ty_tag, nested_type?))),
+                    Err(e) => Some(Either::Right((ty_tag, e))),
+                }
+            })
+            .partition_map(|r| r);
+
+        for (ty_tag, nested_type) in nested_types {
+            self.insert_nested_type(ty_tag, nested_type);
         }
 
+        failures
+    }
+
+    fn make_nested_type(
+        &self,
+        metadata: &CordlMetadata<'_>,
+        tdi: TypeDefinitionIndex,
+    ) -> color_eyre::Result<Option<NestedType>> {
+        let ty_tag = CsTypeTag::TypeDefinitionIndex(tdi);
+        let ty_def = &metadata.metadata.global_metadata.type_definitions[tdi];
+
         let context_tag = self.get_context_root_tag(ty_tag);
         let context_type_data: TypeDefinitionIndex = context_tag.into();
         let context_td = &metadata.metadata.global_metadata.type_definitions[context_type_data];
@@ -162,7 +346,7 @@ impl TypeContextCollection {
                 "Skipping nested type because it's blacklisted! {context_tag:?} {}",
                 context_td.full_name(metadata.metadata, true)
             );
-            return None;
+            return Ok(None);
         }
 
         let nested_inherits_declaring = ty_def.is_assignable_to(context_td, metadata.metadata);
@@ -174,21 +358,34 @@ impl TypeContextCollection {
             );
         }
 
-        match nested_inherits_declaring {
-            true => {
-                // If a nested type inherits its declaring type, move it to its own CppContext
-                let context = TypeContext::make(metadata, tdi, ty_tag);
+        let nested_type = match nested_inherits_declaring {
+            // If a nested type inherits its declaring type, move it to its own CppContext
+            true => NestedType::Unnested(TypeContext::make(metadata, tdi, ty_tag)?),
+            false => {
+                let new_cpp_type = CsType::make_cs_type(metadata, tdi, ty_tag)?
+                    .ok_or_else(|| eyre!("Failed to make nested type"))?;
+
+                NestedType::Nested(new_cpp_type)
+            }
+        };
+
+        Ok(Some(nested_type))
+    }
 
+    fn insert_nested_type(
+        &mut self,
+        ty_tag: CsTypeTag,
+        nested_type: NestedType,
+    ) -> Option<&mut TypeContext> {
+        match nested_type {
+            NestedType::Unnested(context) => {
                 // Unnest type does not alias to another context or type
                 self.alias_context.remove(&ty_tag);
 
                 self.all_contexts.insert(ty_tag, context);
                 self.all_contexts.get_mut(&ty_tag)
             }
-            false => {
-                let new_cpp_type = CsType::make_cs_type(metadata, tdi, ty_tag)
-                    .expect("Failed to make nested type");
-
+            NestedType::Nested(new_cpp_type) => {
                 let context = self.get_context_mut(ty_tag).unwrap();
                 // self.alias_type_to_context(new_cpp_type.self_tag, context_root_tag, true);
 
@@ -207,14 +404,14 @@ impl TypeContextCollection {
         &mut self,
         method_spec: &Il2CppMethodSpec,
         metadata: &mut CordlMetadata,
-    ) -> Option<&mut TypeContext> {
+    ) -> color_eyre::Result<Option<&mut TypeContext>> {
         // Not a generic class, no type needed
         if method_spec.class_inst_index == u32::MAX {
-            return None;
+            return Ok(None);
         }
         // Skip generic methods?
         if method_spec.method_inst_index != u32::MAX {
-            return None;
+            return Ok(None);
         }
 
         let method =
@@ -227,7 +424,7 @@ impl TypeContextCollection {
                 "Skipping make interface for generic instantiation {}",
                 ty_def.full_name(metadata.metadata, true)
             );
-            return None;
+            return Ok(None);
         }
 
         let type_data = CsTypeTag::TypeDefinitionIndex(method.declaring_type);
@@ -240,7 +437,7 @@ impl TypeContextCollection {
                 method_spec.class_inst_index,
                 ty_def.full_name(metadata.metadata, true)
             );
-            return None;
+            return Ok(None);
         }
 
         if self.filling_types.contains(&context_root_tag) {
@@ -258,21 +455,21 @@ impl TypeContextCollection {
         // Why is the borrow checker so dumb?
         // Using entries causes borrow checker to die :(
         if self.filled_types.contains(&generic_class_ty_data) {
-            return Some(self.all_contexts.get_mut(&context_root_tag).unwrap());
+            return Ok(Some(self.all_contexts.get_mut(&context_root_tag).unwrap()));
         }
 
         if self.get_cs_type(generic_class_ty_data).is_some() {
-            return self.get_context_mut(generic_class_ty_data);
+            return Ok(self.get_context_mut(generic_class_ty_data));
         }
 
-        let mut new_cpp_type = CsType::make_cs_type(metadata, tdi, generic_class_ty_data)
-            .expect("Failed to make generic type");
+        let mut new_cpp_type = CsType::make_cs_type(metadata, tdi, generic_class_ty_data)?
+            .ok_or_else(|| eyre!("Failed to make generic type"))?;
 
         let type_resolver = TypeResolver {
             cordl_metadata: metadata,
             collection: self,
         };
-        new_cpp_type.add_class_generic_inst(&generic_inst.types, &type_resolver);
+        new_cpp_type.add_class_generic_inst(&generic_inst.types, &type_resolver)?;
         new_cpp_type.self_tag = generic_class_ty_data;
         self.alias_type_to_context(new_cpp_type.self_tag, context_root_tag);
 
@@ -294,7 +491,7 @@ impl TypeContextCollection {
         // context.insert_cpp_type(stub);
         context.insert_cs_type(new_cpp_type);
 
-        Some(context)
+        Ok(Some(context))
     }
 
     ///
@@ -304,9 +501,9 @@ impl TypeContextCollection {
         &mut self,
         method_spec: &Il2CppMethodSpec,
         metadata: &CordlMetadata,
-    ) -> Option<&mut TypeContext> {
+    ) -> color_eyre::Result<Option<&mut TypeContext>> {
         if method_spec.method_inst_index == u32::MAX {
-            return None;
+            return Ok(None);
         }
 
         let method =
@@ -324,7 +521,7 @@ impl TypeContextCollection {
                 "Skipping {tdi:?} {} since it is blacklisted",
                 ty_def.full_name(metadata.metadata, true)
             );
-            return None;
+            return Ok(None);
         }
 
         if ty_def.is_interface() {
@@ -333,7 +530,7 @@ impl TypeContextCollection {
                 "Skipping fill generic method interface for generic instantiation {}",
                 ty_def.full_name(metadata.metadata, true)
             );
-            return None;
+            return Ok(None);
         }
 
         let context_root_tag = self.get_context_root_tag(type_data);
@@ -353,26 +550,26 @@ impl TypeContextCollection {
                 cordl_metadata: metadata,
                 collection,
             };
-            cpp_type.add_method_generic_inst(method_spec, &type_resolver);
-            cpp_type.create_method(method_index, &type_resolver, true);
+            cpp_type.add_method_generic_inst(method_spec, &type_resolver)?;
+            cpp_type.create_method(method_index, &type_resolver, true)?;
 
-            cpp_type
-        });
+            Ok(cpp_type)
+        })?;
 
-        self.all_contexts.get_mut(&context_root_tag)
+        Ok(self.all_contexts.get_mut(&context_root_tag))
     }
 
     pub fn fill_generic_class_inst(
         &mut self,
         method_spec: &Il2CppMethodSpec,
         metadata: &CordlMetadata,
-    ) -> Option<&mut TypeContext> {
+    ) -> color_eyre::Result<Option<&mut TypeContext>> {
         if method_spec.class_inst_index == u32::MAX {
-            return None;
+            return Ok(None);
         }
         // Skip generic methods?
         if method_spec.method_inst_index != u32::MAX {
-            return None;
+            return Ok(None);
         }
 
         let method =
@@ -389,7 +586,7 @@ impl TypeContextCollection {
                 "Skipping {tdi:?} {} since it is blacklisted",
                 ty_def.full_name(metadata.metadata, true)
             );
-            return None;
+            return Ok(None);
         }
 
         if ty_def.is_interface() {
@@ -398,7 +595,7 @@ impl TypeContextCollection {
                 "Skipping fill class interface for generic instantiation {}",
                 ty_def.full_name(metadata.metadata, true)
             );
-            return None;
+            return Ok(None);
         }
 
         let context_root_tag = self.get_context_root_tag(type_data);
@@ -416,13 +613,13 @@ impl TypeContextCollection {
             generic_class_ty_data,
             |collection: &mut TypeContextCollection, mut cpp_type| {
                 // cpp_type.make_generics_args(metadata, collection);
-                collection.fill_cpp_type(&mut cpp_type, metadata);
+                collection.fill_cpp_type(&mut cpp_type, metadata)?;
 
-                cpp_type
+                Ok(cpp_type)
             },
-        );
+        )?;
 
-        self.all_contexts.get_mut(&context_root_tag)
+        Ok(self.all_contexts.get_mut(&context_root_tag))
     }
 
     pub fn make_from(
@@ -431,7 +628,7 @@ impl TypeContextCollection {
 
         type_data: TypeData,
         _generic_inst: Option<&Vec<usize>>,
-    ) -> &mut TypeContext {
+    ) -> color_eyre::Result<&mut TypeContext> {
         let type_tag = CsType::get_tag_tdi(type_data);
         assert!(
             !metadata.child_to_parent_map.contains_key(&type_tag),
@@ -450,11 +647,64 @@ impl TypeContextCollection {
         // Why is the borrow checker so dumb?
         // Using entries causes borrow checker to die :(
         if self.all_contexts.contains_key(&context_root_tag) {
-            return self.all_contexts.get_mut(&context_root_tag).unwrap();
+            return Ok(self.all_contexts.get_mut(&context_root_tag).unwrap());
         }
 
         let tdi = context_root_tag.get_tdi();
-        let context = TypeContext::make(metadata, tdi, context_root_tag);
+        let context = TypeContext::make(metadata, tdi, context_root_tag)?;
+        Ok(self.insert_context(context_root_tag, context))
+    }
+
+    ///
+    /// Makes the contexts of the root types `tdis` in parallel
+    /// and aliases their nested types to them.
+    /// Returns the types which failed to be made
+    ///
+    pub fn make_all_from(
+        &mut self,
+        metadata: &CordlMetadata,
+        tdis: &[TypeDefinitionIndex],
+    ) -> TypeFailures {
+        let total = tdis.len();
+        let (contexts, failures): (Vec<_>, TypeFailures) = tdis
+            .par_iter()
+            .enumerate()
+            .filter(|(_, tdi)| {
+                !self
+                    .all_contexts
+                    .contains_key(&CsTypeTag::TypeDefinitionIndex(**tdi))
+            })
+            .map(|(i, tdi)| {
+                assert!(
+                    !metadata.child_to_parent_map.contains_key(tdi),
+                    "Cannot create context for nested type",
+                );
+                trace!(
+                    "Making types {:.4}% ({i}/{total})",
+                    (i as f64 / total as f64 * 100.0)
+                );
+
+                let tag = CsTypeTag::TypeDefinitionIndex(*tdi);
+                match TypeContext::make(metadata, *tdi, tag) {
+                    Ok(context) => Either::Left((tag, context)),
+                    Err(e) => Either::Right((tag, e)),
+                }
+            })
+            .partition_map(|r| r);
+
+        for (tag, context) in contexts {
+            self.insert_context(tag, context);
+            self.alias_nested_types_il2cpp(tag.get_tdi(), tag, metadata);
+        }
+
+        failures
+    }
+
+    fn insert_context(
+        &mut self,
+        context_root_tag: CsTypeTag,
+        context: TypeContext,
+    ) -> &mut TypeContext {
         // Now do children
         for cpp_type in context.typedef_types.values() {
             for n in &cpp_type.nested_types {
@@ -484,9 +734,13 @@ impl TypeContextCollection {
             .and_then(|c| c.get_types_mut().get_mut(&ty))
     }
 
-    pub fn borrow_cs_type<F>(&mut self, ty: CsTypeTag, func: F)
+    ///
+    /// Replaces the type with the result of `func`,
+    /// the type is left as it was if `func` fails
+    ///
+    pub fn borrow_cs_type<F>(&mut self, ty: CsTypeTag, func: F) -> color_eyre::Result<()>
     where
-        F: Fn(&mut Self, CsType) -> CsType,
+        F: Fn(&mut Self, CsType) -> color_eyre::Result<CsType>,
     {
         let context_ty = self.get_context_root_tag(ty);
         if self.borrowing_types.contains(&context_ty) {
@@ -505,12 +759,13 @@ impl TypeContextCollection {
         };
         let _old_tag = declaring_cpp_type.self_tag;
         let new_cpp_ty = func(self, declaring_cpp_type);
+        self.borrowing_types.remove(&context_ty);
 
         let context = self.all_contexts.get_mut(&context_ty).unwrap();
 
-        context.insert_cs_type(new_cpp_ty);
+        context.insert_cs_type(new_cpp_ty?);
 
-        self.borrowing_types.remove(&context_ty);
+        Ok(())
     }
 
     pub fn get_context(&self, type_tag: CsTypeTag) -> Option<&TypeContext> {
@@ -67,6 +67,13 @@ pub struct CsMethodData {
     pub slot: Option<u16>,
 }
 
+impl CsMethodData {
+    /// The address relative to the libil2cpp base, if the method has code
+    pub fn address(&self) -> Option<u64> {
+        self.addrs.filter(|a| *a != 0 && *a != u64::MAX)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CsMethodSizeData {
     pub cpp_method_name: String,
@@ -114,6 +121,42 @@ pub enum CsValue {
     Null,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum CsAttributeValue {
+    Value(CsValue),
+    /// `typeof(T)`, the full name of the type or `None` if null
+    Type(Option<String>),
+    Enum {
+        ty: String,
+        value: CsValue,
+    },
+    /// `None` if the array is null
+    Array(Option<Vec<CsAttributeValue>>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CsAttributeNamedArgKind {
+    Field,
+    Property,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsAttributeNamedArg {
+    pub name: String,
+    pub kind: CsAttributeNamedArgKind,
+    pub value: CsAttributeValue,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsCustomAttribute {
+    /// Full name of the attribute type e.g `System.ObsoleteAttribute`
+    pub name: String,
+    pub ty: CsTypeTag,
+    pub ctor: MethodIndex,
+    pub args: Vec<CsAttributeValue>,
+    pub named_args: Vec<CsAttributeNamedArg>,
+}
+
 /// Explicit layout
 /// il2cpp basically turns each field into 2 structs within a union:
 /// 1 which is packed with size 1, and padded with offset to fit to the end
@@ -142,13 +185,27 @@ pub struct CsField {
     pub is_const: bool,
 
     pub offset: Option<u32>,
+    /// Where the offset comes from, `None` for static fields
+    pub offset_source: Option<CsFieldOffsetSource>,
+    /// Offset into the static fields of the class, from the field offsets table.
+    /// `None` for instance, thread static and constant fields and for generic instantiations
+    pub static_offset: Option<u32>,
     pub size: usize,
 
     pub value: Option<CsValue>,
     pub brief_comment: Option<String>,
+    pub attributes: Vec<CsCustomAttribute>,
 }
 
-#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CsFieldOffsetSource {
+    /// Laid out by cordl
+    Computed,
+    /// Read from the field offsets table of the metadata registration
+    Table,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct CsProperty {
     pub name: String,
     pub prop_ty: ResolvedType,
@@ -158,6 +215,19 @@ pub struct CsProperty {
     /// Whether this property is one that's indexable (accessor methods take an index argument)
     pub indexable: bool,
     pub brief_comment: Option<String>,
+    pub attributes: Vec<CsCustomAttribute>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct CsEvent {
+    pub name: String,
+    /// The delegate type of the event
+    pub event_ty: ResolvedType,
+    pub instance: bool,
+    pub add: Option<(MethodIndex, String)>,
+    pub remove: Option<(MethodIndex, String)>,
+    pub raise: Option<(MethodIndex, String)>,
+    pub brief_comment: Option<String>,
 }
 
 bitflags! {
@@ -182,6 +252,7 @@ pub struct CsParam {
     // &&
     pub modifiers: CsParamFlags,
     pub def_value: Option<CsValue>,
+    pub attributes: Vec<CsCustomAttribute>,
 }
 
 bitflags! {
@@ -213,6 +284,7 @@ pub struct CsMethod {
 
     pub method_data: CsMethodData,
     pub method_flags: CSMethodFlags,
+    pub attributes: Vec<CsCustomAttribute>,
 }
 
 // TODO: Generics
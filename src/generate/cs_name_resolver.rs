@@ -0,0 +1,129 @@
+use brocolib::runtime_metadata::Il2CppTypeEnum;
+use itertools::Itertools;
+
+use crate::data::{
+    name_components::NameComponents,
+    type_resolver::{ResolvedType, ResolvedTypeData},
+};
+
+use super::{
+    cs_context_collection::TypeContextCollection, cs_members::CsValue, metadata::CordlMetadata,
+};
+
+///
+/// Resolves types to their C# spelling e.g `System.Collections.Generic.List<int>[]`
+/// For backends which describe the C# API rather than bind to it
+///
+pub struct CsNameResolver<'a, 'b> {
+    pub cordl_metadata: &'a CordlMetadata<'b>,
+    pub collection: &'a TypeContextCollection,
+}
+
+impl CsNameResolver<'_, '_> {
+    pub fn resolve_name(&self, ty: &ResolvedType) -> String {
+        match &ty.data {
+            ResolvedTypeData::Array(array_type) => format!("{}[]", self.resolve_name(array_type)),
+            ResolvedTypeData::Ptr(resolved_type) => {
+                format!("{}*", self.resolve_name(resolved_type))
+            }
+            ResolvedTypeData::ByRef(resolved_type) => {
+                format!("ref {}", self.resolve_name(resolved_type))
+            }
+            ResolvedTypeData::ByRefConst(resolved_type) => {
+                format!("in {}", self.resolve_name(resolved_type))
+            }
+            _ => self.resolve_name_components(ty).combine_all(),
+        }
+    }
+
+    fn resolve_name_components(&self, ty: &ResolvedType) -> NameComponents {
+        let metadata = self.cordl_metadata;
+        match &ty.data {
+            ResolvedTypeData::GenericInst(resolved_type, vec) => {
+                let type_def_name_components = self.resolve_name_components(resolved_type);
+                let generic_types_formatted = vec
+                    .iter()
+                    .map(|(r, _inc)| self.resolve_name(r))
+                    .collect_vec();
+
+                NameComponents {
+                    generics: Some(generic_types_formatted),
+                    ..type_def_name_components
+                }
+            }
+            ResolvedTypeData::GenericArg(gen_param_idx, _)
+            | ResolvedTypeData::GenericMethodArg(_, gen_param_idx, _) => {
+                let generic_param =
+                    &metadata.metadata.global_metadata.generic_parameters[*gen_param_idx];
+
+                generic_param.name(metadata.metadata).to_string().into()
+            }
+            ResolvedTypeData::Type(resolved_tag) => {
+                match self.collection.get_cs_type(*resolved_tag) {
+                    Some(incl_ty) => incl_ty.cs_name_components.clone(),
+                    None => {
+                        let td = &metadata.metadata.global_metadata.type_definitions
+                            [resolved_tag.get_tdi()];
+                        td.full_name(metadata.metadata, true).into()
+                    }
+                }
+            }
+            ResolvedTypeData::Blacklisted(cs_type_tag) => {
+                let td = &metadata.metadata.global_metadata.type_definitions[cs_type_tag.get_tdi()];
+
+                td.full_name(metadata.metadata, true).into()
+            }
+            ResolvedTypeData::Primitive(il2_cpp_type_enum) => {
+                primitive_keyword(*il2_cpp_type_enum).into()
+            }
+            _ => self.resolve_name(ty).into(),
+        }
+    }
+}
+
+/// C# keyword of a primitive e.g `int` for `I4`
+pub fn primitive_keyword(ty: Il2CppTypeEnum) -> String {
+    match ty {
+        Il2CppTypeEnum::Void => "void",
+        Il2CppTypeEnum::Boolean => "bool",
+        Il2CppTypeEnum::Char => "char",
+        Il2CppTypeEnum::I1 => "sbyte",
+        Il2CppTypeEnum::U1 => "byte",
+        Il2CppTypeEnum::I2 => "short",
+        Il2CppTypeEnum::U2 => "ushort",
+        Il2CppTypeEnum::I4 => "int",
+        Il2CppTypeEnum::U4 => "uint",
+        Il2CppTypeEnum::I8 => "long",
+        Il2CppTypeEnum::U8 => "ulong",
+        Il2CppTypeEnum::R4 => "float",
+        Il2CppTypeEnum::R8 => "double",
+        Il2CppTypeEnum::I => "nint",
+        Il2CppTypeEnum::U => "nuint",
+        Il2CppTypeEnum::String => "string",
+        Il2CppTypeEnum::Object => "object",
+        Il2CppTypeEnum::Typedbyref => "System.TypedReference",
+        _ => return format!("{ty:?}"),
+    }
+    .to_string()
+}
+
+/// C# literal of a constant e.g `1.5f`
+pub fn cs_literal(value: &CsValue) -> String {
+    match value {
+        CsValue::String(s) => format!("{s:?}"),
+        CsValue::Char(c) => format!("'{c}'"),
+        CsValue::Bool(b) => b.to_string(),
+        CsValue::U8(x) => x.to_string(),
+        CsValue::U16(x) => x.to_string(),
+        CsValue::U32(x) => x.to_string(),
+        CsValue::U64(x) => x.to_string(),
+        CsValue::I8(x) => x.to_string(),
+        CsValue::I16(x) => x.to_string(),
+        CsValue::I32(x) => x.to_string(),
+        CsValue::I64(x) => x.to_string(),
+        CsValue::F32(f) => format!("{f:?}f"),
+        CsValue::F64(f) => format!("{f:?}"),
+        CsValue::Object(_) | CsValue::Null => "null".to_string(),
+        CsValue::ValueType(_) => "default".to_string(),
+    }
+}
@@ -4,6 +4,7 @@ use std::{
 };
 
 use byteorder::ReadBytesExt;
+use color_eyre::eyre::{bail, ensure, eyre};
 
 use brocolib::{
     global_metadata::{
@@ -30,11 +31,12 @@ use crate::{
 
 use super::{
     cs_members::{
-        CSMethodFlags, CsConstructor, CsGenericTemplate, CsMethod, CsMethodData, CsParam,
-        CsParamFlags, CsProperty, CsValue,
+        CSMethodFlags, CsConstructor, CsCustomAttribute, CsEvent, CsFieldOffsetSource,
+        CsGenericTemplate, CsMethod, CsMethodData, CsParam, CsProperty, CsValue,
     },
     cs_type_tag::CsTypeTag,
-    metadata::CordlMetadata,
+    custom_attributes::get_custom_attributes,
+    metadata::{CordlMetadata, PointerSize},
     offsets::{self, SizeInfo},
     type_extensions::{
         MethodDefintionExtensions, TypeDefinitionExtensions, TypeDefinitionIndexExtensions,
@@ -74,7 +76,9 @@ pub struct CsType {
     pub fields: Vec<CsField>,
     pub methods: Vec<CsMethod>,
     pub properties: Vec<CsProperty>,
+    pub events: Vec<CsEvent>,
     pub constructors: Vec<CsConstructor>,
+    pub attributes: Vec<CsCustomAttribute>,
 
     pub is_value_type: bool,
     pub is_enum_type: bool,
@@ -114,6 +118,15 @@ impl CsType {
         &self.nested_types
     }
 
+    /// Whether this is an enum marked with `[System.Flags]`
+    pub fn is_flags_enum(&self) -> bool {
+        self.is_enum_type
+            && self
+                .attributes
+                .iter()
+                .any(|a| a.name == "System.FlagsAttribute")
+    }
+
     pub fn get_tag_tdi(tag: TypeData) -> TypeDefinitionIndex {
         match tag {
             TypeData::TypeDefinitionIndex(tdi) => tdi,
@@ -128,7 +141,7 @@ impl CsType {
         &mut self,
         method_spec: &Il2CppMethodSpec,
         type_resolver: &TypeResolver,
-    ) -> &mut CsType {
+    ) -> color_eyre::Result<&mut CsType> {
         assert!(method_spec.method_inst_index != u32::MAX);
 
         let metadata = type_resolver.cordl_metadata;
@@ -136,23 +149,23 @@ impl CsType {
             .metadata_registration
             .generic_insts
             .get(method_spec.method_inst_index as usize)
-            .unwrap();
+            .ok_or_else(|| eyre!("No generic inst {}", method_spec.method_inst_index))?;
 
         let args = inst
             .types
             .iter()
             .map(|t| type_resolver.resolve_type(self, *t, TypeUsage::TypeName, true))
-            .collect();
+            .collect::<color_eyre::Result<_>>()?;
         self.method_generic_instantiation_map
             .insert(method_spec.method_definition_index, args);
 
-        self
+        Ok(self)
     }
     pub fn add_class_generic_inst(
         &mut self,
         generic_inst: &[usize],
         type_resolver: &TypeResolver,
-    ) -> &mut CsType {
+    ) -> color_eyre::Result<&mut CsType> {
         let metadata = type_resolver.cordl_metadata;
 
         let tdi = self.self_tag.get_tdi();
@@ -162,23 +175,28 @@ impl CsType {
         // We really just want to call it once for a given size and then move on
         // Every type should have a valid metadata size, even if it is 0
 
-        self.size_info = Some(offsets::get_size_info(t, tdi, Some(generic_inst), metadata));
+        self.size_info = Some(offsets::get_size_info(
+            t,
+            tdi,
+            Some(generic_inst),
+            metadata,
+        )?);
 
         self.generic_instantiations_args_types = Some(
             generic_inst
                 .iter()
                 .map(|t| type_resolver.resolve_type(self, *t, TypeUsage::TypeName, true))
-                .collect(),
+                .collect::<color_eyre::Result<_>>()?,
         );
 
-        self
+        Ok(self)
     }
 
     pub fn make_cs_type(
         metadata: &CordlMetadata,
         tdi: TypeDefinitionIndex,
         tag: CsTypeTag,
-    ) -> Option<CsType> {
+    ) -> color_eyre::Result<Option<CsType>> {
         // let iface = metadata.interfaces.get(t.interfaces_start);
         // Then, handle interfaces
 
@@ -211,7 +229,7 @@ impl CsType {
         if metadata.blacklisted_types.contains(&tdi) {
             info!("Skipping {full_name} ({tdi:?}) because it's blacklisted");
 
-            return None;
+            return Ok(None);
         }
 
         // all nested types are unnested
@@ -222,8 +240,9 @@ impl CsType {
                 .get(t.declaring_type_index as usize)
                 .unwrap()
         });
-        let declaring_tag =
-            declaring_ty.map(|t| CsTypeTag::from_type_data(t.data, metadata.metadata));
+        let declaring_tag = declaring_ty
+            .map(|t| CsTypeTag::from_type_data(t.data, metadata.metadata))
+            .transpose()?;
 
         let cs_name_components = t.get_name_components(metadata.metadata);
         let is_pointer = t.is_reference_type(metadata.metadata);
@@ -231,7 +250,7 @@ impl CsType {
         // TODO: Come up with a way to avoid this extra call to layout the entire type
         // We really just want to call it once for a given size and then move on
         // Every type should have a valid metadata size, even if it is 0
-        let size_info: offsets::SizeInfo = offsets::get_size_info(t, tdi, None, metadata);
+        let size_info: offsets::SizeInfo = offsets::get_size_info(t, tdi, None, metadata)?;
 
         // best results of cordl are when specified packing is strictly what is used, but experimentation may be required
         let packing = size_info.specified_packing;
@@ -249,7 +268,9 @@ impl CsType {
             fields: Default::default(),
             methods: Default::default(),
             properties: Default::default(),
+            events: Default::default(),
             constructors: Default::default(),
+            attributes: get_custom_attributes(metadata, tdi, u32::from(t.token)),
 
             is_value_type: t.is_value_type(),
             is_enum_type: t.is_enum_type(),
@@ -273,7 +294,7 @@ impl CsType {
         if t.parent_index == u32::MAX {
             if !t.is_interface() && t.full_name(metadata.metadata, true) != "System.Object" {
                 info!("Skipping type: {ns}::{name} because it has parent index: {} and is not an interface!", t.parent_index);
-                return None;
+                return Ok(None);
             }
         } else if metadata
             .metadata_registration
@@ -281,20 +302,21 @@ impl CsType {
             .get(t.parent_index as usize)
             .is_none()
         {
-            panic!("NO PARENT! But valid index found: {}", t.parent_index);
+            bail!("NO PARENT! But valid index found: {}", t.parent_index);
         }
 
-        Some(cpptype)
+        Ok(Some(cpptype))
     }
 
-    pub fn fill_from_il2cpp(&mut self, type_resolver: &TypeResolver) {
-        self.make_parents(type_resolver);
-        self.make_interfaces(type_resolver);
+    pub fn fill_from_il2cpp(&mut self, type_resolver: &TypeResolver) -> color_eyre::Result<()> {
+        self.make_parents(type_resolver)?;
+        self.make_interfaces(type_resolver)?;
 
         self.make_nested_types(type_resolver);
-        self.make_fields(type_resolver);
-        self.make_properties(type_resolver);
-        self.make_methods(type_resolver);
+        self.make_fields(type_resolver)?;
+        self.make_properties(type_resolver)?;
+        self.make_events(type_resolver)?;
+        self.make_methods(type_resolver)?;
 
         let metadata = type_resolver.cordl_metadata;
         let tdi = self.self_tag.get_tdi();
@@ -305,17 +327,19 @@ impl CsType {
                 .metadata_registration
                 .types
                 .get(t.element_type_index as usize)
-                .unwrap();
+                .ok_or_else(|| eyre!("No enum element type {}", t.element_type_index))?;
 
             self.enum_backing_type = Some(element_type.ty);
         }
+
+        Ok(())
     }
 
     fn make_parameters(
         &mut self,
         method: &brocolib::global_metadata::Il2CppMethodDefinition,
         type_resolver: &TypeResolver,
-    ) -> Vec<CsParam> {
+    ) -> color_eyre::Result<Vec<CsParam>> {
         let metadata = type_resolver.cordl_metadata;
         let _tdi = self.self_tag.get_tdi();
 
@@ -336,19 +360,19 @@ impl CsType {
         param: &brocolib::global_metadata::Il2CppParameterDefinition,
         param_index: ParameterIndex,
         type_resolver: &TypeResolver,
-    ) -> CsParam {
+    ) -> color_eyre::Result<CsParam> {
         let metadata = type_resolver.cordl_metadata;
-        let _tdi = self.self_tag.get_tdi();
+        let tdi = self.self_tag.get_tdi();
 
-        let _param_type = metadata
+        let param_type = metadata
             .metadata_registration
             .types
             .get(param.type_index as usize)
             .unwrap();
 
-        let def_value = Self::param_default_value(metadata, param_index);
+        let def_value = Self::param_default_value(metadata, param_index)?;
 
-        CsParam {
+        Ok(CsParam {
             name: param.name(metadata.metadata).to_owned(),
             def_value,
             il2cpp_ty: type_resolver.resolve_type(
@@ -356,12 +380,13 @@ impl CsType {
                 param.type_index as usize,
                 TypeUsage::Parameter,
                 false,
-            ),
-            modifiers: CsParamFlags::empty(),
-        }
+            )?,
+            modifiers: param_type.param_flags(),
+            attributes: get_custom_attributes(metadata, tdi, u32::from(param.token)),
+        })
     }
 
-    fn make_methods(&mut self, type_resolver: &TypeResolver) {
+    fn make_methods(&mut self, type_resolver: &TypeResolver) -> color_eyre::Result<()> {
         let metadata = type_resolver.cordl_metadata;
         let tdi = self.self_tag.get_tdi();
         let t = Self::get_type_definition(metadata, tdi);
@@ -375,19 +400,21 @@ impl CsType {
             // Then, for each method, write it out
             for (i, _method) in t.methods(metadata.metadata).iter().enumerate() {
                 let method_index = MethodIndex::new(t.method_start.index() + i as u32);
-                self.create_method(method_index, type_resolver, false);
+                self.create_method(method_index, type_resolver, false)?;
             }
         }
+
+        Ok(())
     }
 
-    fn make_fields(&mut self, type_resolver: &TypeResolver) {
+    fn make_fields(&mut self, type_resolver: &TypeResolver) -> color_eyre::Result<()> {
         let metadata = type_resolver.cordl_metadata;
         let tdi = self.self_tag.get_tdi();
         let t = Self::get_type_definition(metadata, tdi);
 
         // if no fields, skip
         if t.field_count == 0 {
-            return;
+            return Ok(());
         }
 
         let field_offsets = &metadata
@@ -417,7 +444,7 @@ impl CsType {
                 generic_inst_types.as_deref(),
                 Some(&mut offsets),
                 false,
-            );
+            )?;
         }
         let mut offset_iter = offsets.iter();
 
@@ -428,7 +455,7 @@ impl CsType {
             field_offsets: &[u32],
             metadata: &CordlMetadata<'_>,
             t: &Il2CppTypeDefinition,
-        ) -> Option<u32> {
+        ) -> Option<(u32, CsFieldOffsetSource)> {
             let f_type = metadata
                 .metadata_registration
                 .types
@@ -437,15 +464,20 @@ impl CsType {
             let f_name = field.name(metadata.metadata);
 
             match f_type.is_static() || f_type.is_constant() {
-                // return u32::MAX for static fields as an "invalid offset" value
-                true => None,
+                // constants have no storage
+                true if f_type.is_constant() => None,
+                // static fields are offsets into the static fields of the class,
+                // thread static fields are stored per thread and have an offset of -1
+                true => Some(field_offsets[i])
+                    .filter(|offset| *offset != u32::MAX)
+                    .map(|offset| (offset, CsFieldOffsetSource::Table)),
                 false => Some({
                     // If we have a hotfix offset, use that instead
                     // We can safely assume this always returns None even if we "next" past the end
-                    let offset = if let Some(computed_offset) = iter.next() {
-                        *computed_offset
+                    let (offset, source) = if let Some(computed_offset) = iter.next() {
+                        (*computed_offset, CsFieldOffsetSource::Computed)
                     } else {
-                        field_offsets[i]
+                        (field_offsets[i], CsFieldOffsetSource::Table)
                     };
 
                     if offset < metadata.object_size() as u32 {
@@ -457,13 +489,16 @@ impl CsType {
                     }
 
                     // TODO: Is the offset supposed to be smaller than object size for fixups?
-                    match t.is_value_type() && offset >= metadata.object_size() as u32 {
+                    let offset = match t.is_value_type() && offset >= metadata.object_size() as u32
+                    {
                         true => {
                             // value type fixup
                             offset - metadata.object_size() as u32
                         }
                         false => offset,
-                    }
+                    };
+
+                    (offset, source)
                 }),
             }
         }
@@ -472,7 +507,7 @@ impl CsType {
             field: &Il2CppFieldDefinition,
             gen_args: Option<&Vec<ResolvedType>>,
             metadata: &CordlMetadata<'_>,
-        ) -> usize {
+        ) -> color_eyre::Result<usize> {
             let f_type = metadata
                 .metadata_registration
                 .types
@@ -481,9 +516,9 @@ impl CsType {
 
             let generic_inst_types: Option<Vec<usize>> =
                 gen_args.map(|list| list.iter().map(|t| t.ty).collect_vec());
-            let sa = offsets::get_il2cpptype_sa(metadata, f_type, generic_inst_types.as_deref());
+            let sa = offsets::get_il2cpptype_sa(metadata, f_type, generic_inst_types.as_deref())?;
 
-            sa.size
+            Ok(sa.size)
         }
 
         let fields = t
@@ -500,38 +535,49 @@ impl CsType {
                 let field_index = FieldIndex::new(t.field_start.index() + i as u32);
                 let f_name = field.name(metadata.metadata);
 
+                let instance = !f_type.is_static() && !f_type.is_constant();
                 let f_offset = get_offset(field, i, &mut offset_iter, field_offsets, metadata, t);
+                // the table has no static offsets for generic instantiations, il2cpp lays them out at runtime
+                let (f_offset, static_offset) = match instance {
+                    true => (f_offset, None),
+                    false => (None, f_offset.filter(|_| self.generic_instantiations_args_types.is_none())),
+                };
 
                 // calculate / fetch the field size
-                let f_size = get_size(field, self.generic_instantiations_args_types.as_ref(), metadata);
+                let f_size = get_size(field, self.generic_instantiations_args_types.as_ref(), metadata)?;
 
 
                 // TODO: Check a flag to look for default values to speed this up
-                let def_value = Self::field_default_value(metadata, field_index);
+                let def_value = Self::field_default_value(metadata, field_index)?;
 
-                assert!(def_value.is_none() || (def_value.is_some() && f_type.is_param_optional()));
+                ensure!(def_value.is_none() || f_type.is_param_optional(), "Field {f_name} has a default value but is not optional");
 
 
-                CsField {
+                Ok(CsField {
                     name: f_name.to_owned(),
-                    field_ty: type_resolver.resolve_type(self, field.type_index as usize, TypeUsage::Field, true),
-                    offset: f_offset,
+                    field_ty: type_resolver.resolve_type(self, field.type_index as usize, TypeUsage::Field, true)?,
+                    offset: f_offset.map(|(offset, _)| offset),
+                    offset_source: f_offset.map(|(_, source)| source),
+                    static_offset: static_offset.map(|(offset, _)| offset),
                     size: f_size,
-                    instance: !f_type.is_static() && !f_type.is_constant(),
+                    instance,
                     readonly: f_type.is_constant(),
                     brief_comment: Some(format!("Field {f_name}, offset: 0x{:x}, size: 0x{f_size:x}, def value: {def_value:?}", f_offset.unwrap_or(u32::MAX))),
                     is_const: f_type.is_constant() || def_value.is_some(),
                     value: def_value,
-                }
+                    attributes: get_custom_attributes(metadata, tdi, u32::from(field.token)),
+                })
             })
-            .collect_vec();
+            .collect::<color_eyre::Result<Vec<_>>>()?;
 
         for f in fields {
             self.fields.push(f);
         }
+
+        Ok(())
     }
 
-    fn make_parents(&mut self, type_resolver: &TypeResolver) {
+    fn make_parents(&mut self, type_resolver: &TypeResolver) -> color_eyre::Result<()> {
         let metadata = type_resolver.cordl_metadata;
         let tdi = self.self_tag.get_tdi();
 
@@ -551,14 +597,14 @@ impl CsType {
                     info!("Skipping type: {ns}::{name} because it has parent index: {} and is not an interface!", t.parent_index);
                 }
             }
-            return;
+            return Ok(());
         }
 
         let parent_type = metadata
             .metadata_registration
             .types
             .get(t.parent_index as usize)
-            .unwrap_or_else(|| panic!("NO PARENT! But valid index found: {}", t.parent_index));
+            .ok_or_else(|| eyre!("NO PARENT! But valid index found: {}", t.parent_index))?;
 
         // handle value types and enum types specially
         if !t.is_value_type() || t.is_enum_type() {
@@ -567,18 +613,23 @@ impl CsType {
                 parent_type.ty,
                 Il2CppTypeEnum::Class | Il2CppTypeEnum::Genericinst | Il2CppTypeEnum::Object
             );
-            assert!(is_ref_type, "Not a class, object or generic inst!");
+            ensure!(
+                is_ref_type,
+                "Parent is not a class, object or generic inst!"
+            );
 
             self.parent = Some(type_resolver.resolve_type(
                 self,
                 t.parent_index as usize,
                 TypeUsage::TypeName,
                 true,
-            ));
+            )?);
         }
+
+        Ok(())
     }
 
-    fn make_interfaces(&mut self, type_resolver: &TypeResolver) {
+    fn make_interfaces(&mut self, type_resolver: &TypeResolver) -> color_eyre::Result<()> {
         let metadata = type_resolver.cordl_metadata;
         let tdi = self.self_tag.get_tdi();
         let t = &metadata.metadata.global_metadata.type_definitions[tdi];
@@ -591,9 +642,11 @@ impl CsType {
                 interface_index as usize,
                 TypeUsage::TypeName,
                 true,
-            );
+            )?;
             self.interfaces.push(resolved);
         }
+
+        Ok(())
     }
 
     fn make_nested_types(&mut self, type_resolver: &TypeResolver) {
@@ -616,14 +669,14 @@ impl CsType {
             .collect();
     }
 
-    fn make_properties(&mut self, type_resolver: &TypeResolver) {
+    fn make_properties(&mut self, type_resolver: &TypeResolver) -> color_eyre::Result<()> {
         let metadata = type_resolver.cordl_metadata;
         let tdi = self.self_tag.get_tdi();
         let t = Self::get_type_definition(metadata, tdi);
 
         // Then, handle properties
         if t.property_count == 0 {
-            return;
+            return Ok(());
         }
 
         self.properties.reserve(t.property_count as usize);
@@ -655,7 +708,8 @@ impl CsType {
             let index = p_getter.is_some_and(|p| p.parameter_count > 0);
 
             // Need to include this type
-            let prop_ty = type_resolver.resolve_type(self, p_type_index, TypeUsage::Property, true);
+            let prop_ty =
+                type_resolver.resolve_type(self, p_type_index, TypeUsage::Property, true)?;
             self.properties.push(CsProperty {
                 name: p_name.to_owned(),
                 prop_ty,
@@ -675,8 +729,68 @@ impl CsType {
                 indexable: index,
                 brief_comment: None,
                 instance: true,
+                attributes: get_custom_attributes(metadata, tdi, u32::from(prop.token)),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn make_events(&mut self, type_resolver: &TypeResolver) -> color_eyre::Result<()> {
+        let metadata = type_resolver.cordl_metadata;
+        let tdi = self.self_tag.get_tdi();
+        let t = Self::get_type_definition(metadata, tdi);
+
+        if t.event_count == 0 {
+            return Ok(());
+        }
+
+        self.events.reserve(t.event_count as usize);
+        for event in t.events(metadata.metadata) {
+            let e_name = event.name(metadata.metadata);
+
+            // add/remove/raise are relative to the declaring type's methods
+            let make_accessor = |relative_index: u32| {
+                (relative_index != u32::MAX).then(|| {
+                    let method_index = MethodIndex::new(t.method_start.index() + relative_index);
+                    let method = &metadata.metadata.global_metadata.methods[method_index];
+                    (method_index, method.name(metadata.metadata).to_string())
+                })
+            };
+
+            let add = make_accessor(event.add);
+            let remove = make_accessor(event.remove);
+            let raise = make_accessor(event.raise);
+
+            let instance = add
+                .iter()
+                .chain(remove.iter())
+                .chain(raise.iter())
+                .next()
+                .map(|(mi, _)| !metadata.metadata.global_metadata.methods[*mi].is_static_method())
+                .unwrap_or(true);
+
+            // Need to include this type
+            let event_ty = type_resolver.resolve_type(
+                self,
+                event.type_index as usize,
+                TypeUsage::Property,
+                true,
+            )?;
+
+            self.events.push(CsEvent {
+                name: e_name.to_owned(),
+                event_ty,
+                instance,
+                // methods generated in make_methods
+                add,
+                remove,
+                raise,
+                brief_comment: None,
             });
         }
+
+        Ok(())
     }
 
     pub fn create_method(
@@ -684,7 +798,7 @@ impl CsType {
         method_index: MethodIndex,
         type_resolver: &TypeResolver,
         is_generic_method_inst: bool,
-    ) {
+    ) -> color_eyre::Result<()> {
         let metadata = type_resolver.cordl_metadata;
         let method = &metadata.metadata.global_metadata.methods[method_index];
 
@@ -692,7 +806,7 @@ impl CsType {
         let m_name = method.name(metadata.metadata);
         if m_name == ".cctor" {
             // info!("Skipping {}", m_name);
-            return;
+            return Ok(());
         }
 
         let _m_ret_type = metadata
@@ -701,16 +815,7 @@ impl CsType {
             .get(method.return_type as usize)
             .unwrap();
 
-        let m_params_with_def: Vec<CsParam> = self.make_parameters(method, type_resolver);
-
-        let m_params_no_def: Vec<CsParam> = m_params_with_def
-            .iter()
-            .cloned()
-            .map(|mut p| {
-                p.def_value = None;
-                p
-            })
-            .collect_vec();
+        let m_params_with_def: Vec<CsParam> = self.make_parameters(method, type_resolver)?;
 
         // TODO: Add template<typename ...> if a generic inst e.g
         // T UnityEngine.Component::GetComponent<T>() -> bs_hook::Il2CppWrapperType UnityEngine.Component::GetComponent()
@@ -759,8 +864,11 @@ impl CsType {
         if method.is_static_method() {
             flag = flag.union(CSMethodFlags::STATIC);
         }
-        if method.is_virtual_method() {
-            flag = flag.union(CSMethodFlags::VIRTUAL);
+        if method.is_abstract_method() {
+            flag = flag.union(CSMethodFlags::ABSTRACT);
+        }
+        if method.is_override_method() {
+            flag = flag.union(CSMethodFlags::OVERRIDE);
         }
         if method.is_special_name() {
             flag = flag.union(CSMethodFlags::SPECIAL_NAME);
@@ -794,12 +902,17 @@ impl CsType {
                 method.return_type as usize,
                 TypeUsage::ReturnType,
                 true,
-            ),
+            )?,
             declaring_type: method.declaring_type.into(),
-            parameters: m_params_no_def.clone(),
+            parameters: m_params_with_def,
             instance: !method.is_static_method(),
             template: template.clone(),
             method_data,
+            attributes: get_custom_attributes(
+                metadata,
+                method.declaring_type,
+                u32::from(method.token),
+            ),
         };
 
         // if type is a generic
@@ -821,6 +934,8 @@ impl CsType {
         if !is_generic_method_inst {
             self.methods.push(method_decl);
         }
+
+        Ok(())
     }
 
     fn default_value_blob(
@@ -829,53 +944,55 @@ impl CsType {
         data_index: usize,
         _string_quotes: bool,
         _string_as_u16: bool,
-    ) -> CsValue {
-        let data = &metadata
+    ) -> color_eyre::Result<CsValue> {
+        let data = metadata
             .metadata
             .global_metadata
             .field_and_parameter_default_value_data
-            .as_vec()[data_index..];
+            .as_vec()
+            .get(data_index..)
+            .ok_or_else(|| eyre!("Default value data index {data_index} out of bounds"))?;
 
         let mut cursor = Cursor::new(data);
 
-        match ty.ty {
-            Il2CppTypeEnum::Boolean => CsValue::Bool(data[0] != 0),
-            Il2CppTypeEnum::I1 => CsValue::I8(cursor.read_i8().unwrap()),
-            Il2CppTypeEnum::I2 => CsValue::I16(cursor.read_i16::<Endian>().unwrap()),
-            Il2CppTypeEnum::I4 => CsValue::I32(cursor.read_compressed_i32::<Endian>().unwrap()),
-            // TODO: We assume 64 bit
-            Il2CppTypeEnum::I | Il2CppTypeEnum::I8 => {
-                CsValue::I64(cursor.read_i64::<Endian>().unwrap())
+        let value = match ty.ty {
+            Il2CppTypeEnum::Boolean => CsValue::Bool(cursor.read_u8()? != 0),
+            Il2CppTypeEnum::I1 => CsValue::I8(cursor.read_i8()?),
+            Il2CppTypeEnum::I2 => CsValue::I16(cursor.read_i16::<Endian>()?),
+            Il2CppTypeEnum::I4 => CsValue::I32(cursor.read_compressed_i32::<Endian>()?),
+            Il2CppTypeEnum::I if metadata.pointer_size == PointerSize::Bytes4 => {
+                CsValue::I32(cursor.read_i32::<Endian>()?)
             }
-            Il2CppTypeEnum::U1 => CsValue::U8(cursor.read_u8().unwrap()),
-            Il2CppTypeEnum::U2 => CsValue::U16(cursor.read_u16::<Endian>().unwrap()),
-            Il2CppTypeEnum::U4 => CsValue::U32(cursor.read_compressed_u32::<Endian>().unwrap()),
-            // TODO: We assume 64 bit
-            Il2CppTypeEnum::U | Il2CppTypeEnum::U8 => {
-                CsValue::U64(cursor.read_u64::<Endian>().unwrap())
+            Il2CppTypeEnum::I | Il2CppTypeEnum::I8 => CsValue::I64(cursor.read_i64::<Endian>()?),
+            Il2CppTypeEnum::U1 => CsValue::U8(cursor.read_u8()?),
+            Il2CppTypeEnum::U2 => CsValue::U16(cursor.read_u16::<Endian>()?),
+            Il2CppTypeEnum::U4 => CsValue::U32(cursor.read_compressed_u32::<Endian>()?),
+            Il2CppTypeEnum::U if metadata.pointer_size == PointerSize::Bytes4 => {
+                CsValue::U32(cursor.read_u32::<Endian>()?)
             }
+            Il2CppTypeEnum::U | Il2CppTypeEnum::U8 => CsValue::U64(cursor.read_u64::<Endian>()?),
             // https://learn.microsoft.com/en-us/nimbusml/concepts/types
             // https://en.cppreference.com/w/cpp/types/floating-point
-            Il2CppTypeEnum::R4 => CsValue::F32(cursor.read_f32::<Endian>().unwrap()),
-            Il2CppTypeEnum::R8 => CsValue::F64(cursor.read_f64::<Endian>().unwrap()),
+            Il2CppTypeEnum::R4 => CsValue::F32(cursor.read_f32::<Endian>()?),
+            Il2CppTypeEnum::R8 => CsValue::F64(cursor.read_f64::<Endian>()?),
             Il2CppTypeEnum::Char => {
-                let res = String::from_utf16_lossy(&[cursor.read_u16::<Endian>().unwrap()])
+                let res = String::from_utf16_lossy(&[cursor.read_u16::<Endian>()?])
                     .escape_default()
                     .to_string();
 
                 CsValue::Char(res)
             }
             Il2CppTypeEnum::String => {
-                let stru16_len = cursor.read_compressed_i32::<Endian>().unwrap();
+                let stru16_len = cursor.read_compressed_i32::<Endian>()?;
                 if stru16_len == -1 {
-                    return CsValue::String("".to_string());
+                    return Ok(CsValue::String("".to_string()));
                 }
 
                 let mut buf = vec![0u8; stru16_len as usize];
 
-                cursor.read_exact(buf.as_mut_slice()).unwrap();
+                cursor.read_exact(buf.as_mut_slice())?;
 
-                let res = String::from_utf8(buf).unwrap().escape_default().to_string();
+                let res = String::from_utf8(buf)?.escape_default().to_string();
 
                 CsValue::String(res)
             }
@@ -892,14 +1009,16 @@ impl CsType {
                 CsValue::Null
             }
 
-            _ => todo!("Unsupported blob type {:#?}", ty),
-        }
+            _ => bail!("Unsupported blob type {:#?}", ty),
+        };
+
+        Ok(value)
     }
 
     fn unbox_nullable_valuetype<'a>(
         metadata: &'a CordlMetadata,
         ty: &'a Il2CppType,
-    ) -> &'a Il2CppType {
+    ) -> color_eyre::Result<&'a Il2CppType> {
         if let Il2CppTypeEnum::Valuetype = ty.ty {
             match ty.data {
                 TypeData::TypeDefinitionIndex(tdi) => {
@@ -913,65 +1032,74 @@ impl CsType {
                             .metadata_registration
                             .types
                             .get(type_def.byval_type_index as usize)
-                            .unwrap();
+                            .ok_or_else(|| {
+                                eyre!("No Nullable`1 type {}", type_def.byval_type_index)
+                            });
                     }
                 }
-                _ => todo!(),
+                _ => bail!("Unsupported value type data {:?}", ty.data),
             }
         }
 
-        ty
+        Ok(ty)
     }
 
     pub fn field_default_value(
         metadata: &CordlMetadata,
         field_index: FieldIndex,
-    ) -> Option<CsValue> {
+    ) -> color_eyre::Result<Option<CsValue>> {
         metadata
-            .metadata
-            .global_metadata
-            .field_default_values
-            .as_vec()
-            .iter()
-            .find(|f| f.field_index == field_index)
+            .field_default_value_indices
+            .get(&field_index)
+            .map(|i| {
+                &metadata
+                    .metadata
+                    .global_metadata
+                    .field_default_values
+                    .as_vec()[*i]
+            })
             .map(|def| {
                 let ty: &Il2CppType = metadata
                     .metadata_registration
                     .types
                     .get(def.type_index as usize)
-                    .unwrap();
+                    .ok_or_else(|| eyre!("No default value type {}", def.type_index))?;
 
                 // get default value for given type
                 if !def.data_index.is_valid() {
-                    return CsValue::Null;
+                    return Ok(CsValue::Null);
                 }
 
                 Self::default_value_blob(metadata, ty, def.data_index.index() as usize, true, true)
             })
+            .transpose()
     }
     fn param_default_value(
         metadata: &CordlMetadata,
         parameter_index: ParameterIndex,
-    ) -> Option<CsValue> {
+    ) -> color_eyre::Result<Option<CsValue>> {
         metadata
-            .metadata
-            .global_metadata
-            .parameter_default_values
-            .as_vec()
-            .iter()
-            .find(|p| p.parameter_index == parameter_index)
+            .parameter_default_value_indices
+            .get(&parameter_index)
+            .map(|i| {
+                &metadata
+                    .metadata
+                    .global_metadata
+                    .parameter_default_values
+                    .as_vec()[*i]
+            })
             .map(|def| {
                 let mut ty = metadata
                     .metadata_registration
                     .types
                     .get(def.type_index as usize)
-                    .unwrap();
+                    .ok_or_else(|| eyre!("No default value type {}", def.type_index))?;
 
-                ty = Self::unbox_nullable_valuetype(metadata, ty);
+                ty = Self::unbox_nullable_valuetype(metadata, ty)?;
 
                 // This occurs when the type is `null` or `default(T)` for value types
                 if !def.data_index.is_valid() {
-                    return CsValue::Null;
+                    return Ok(CsValue::Null);
                 }
 
                 if let Il2CppTypeEnum::Valuetype = ty.ty {
@@ -987,15 +1115,18 @@ impl CsType {
                                     .metadata_registration
                                     .types
                                     .get(type_def.byval_type_index as usize)
-                                    .unwrap();
+                                    .ok_or_else(|| {
+                                        eyre!("No Nullable`1 type {}", type_def.byval_type_index)
+                                    })?;
                             }
                         }
-                        _ => todo!(),
+                        _ => bail!("Unsupported value type data {:?}", ty.data),
                     }
                 }
 
                 Self::default_value_blob(metadata, ty, def.data_index.index() as usize, true, true)
             })
+            .transpose()
     }
 
     pub fn get_type_definition<'a>(
@@ -3,6 +3,7 @@ use brocolib;
 use brocolib::runtime_metadata::TypeData;
 
 use brocolib::global_metadata::TypeDefinitionIndex;
+use color_eyre::eyre::{bail, eyre};
 
 // TODO:
 /// Indices into the [`Il2CppMetadataRegistration::generic_insts`] field
@@ -62,7 +63,7 @@ impl CsTypeTag {
     pub fn from_generic_class_index(
         generic_class_idx: usize,
         metadata: &brocolib::Metadata,
-    ) -> Self {
+    ) -> color_eyre::Result<Self> {
         let generic_class = &metadata
             .runtime_metadata
             .metadata_registration
@@ -72,25 +73,28 @@ impl CsTypeTag {
             metadata.runtime_metadata.metadata_registration.types[generic_class.type_index];
         // Unwrap
         let TypeData::TypeDefinitionIndex(tdi) = ty.data else {
-            panic!("No TDI for generic inst!")
+            bail!("No TDI for generic inst {generic_class_idx}!")
         };
 
-        Self::GenericInstantiation(GenericInstantiation {
+        Ok(Self::GenericInstantiation(GenericInstantiation {
             tdi,
             inst: generic_class
                 .context
                 .class_inst_idx
-                .expect("Not a generic class inst idx"),
-        })
+                .ok_or_else(|| eyre!("Not a generic class inst idx {generic_class_idx}"))?,
+        }))
     }
-    pub fn from_type_data(type_data: TypeData, metadata: &brocolib::Metadata) -> Self {
+    pub fn from_type_data(
+        type_data: TypeData,
+        metadata: &brocolib::Metadata,
+    ) -> color_eyre::Result<Self> {
         match type_data {
-            TypeData::TypeDefinitionIndex(tdi) => tdi.into(),
-            TypeData::TypeIndex(_) => panic!("Not supported on array! {type_data:#?}"),
+            TypeData::TypeDefinitionIndex(tdi) => Ok(tdi.into()),
+            TypeData::TypeIndex(_) => bail!("Not supported on array! {type_data:#?}"),
             TypeData::GenericClassIndex(generic_class_idx) => {
                 Self::from_generic_class_index(generic_class_idx, metadata)
             }
-            _ => todo!("{type_data:#?}"),
+            _ => bail!("Unsupported type data {type_data:#?}"),
         }
     }
 
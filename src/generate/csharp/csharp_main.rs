@@ -0,0 +1,68 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+};
+
+use itertools::Itertools;
+use log::info;
+
+use crate::generate::{
+    cs_context_collection::TypeContextCollection,
+    cs_name_resolver::CsNameResolver,
+    cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata,
+    output::{OutputFiles, OutputStats},
+};
+
+use super::csharp_writer::CsDumpWriter;
+
+///
+/// Writes the C# declarations of every type definition to `folder`,
+/// as `dump.cs` or one `<namespace>.cs` per namespace
+///
+pub fn run_csharp(
+    cs_collection: &TypeContextCollection,
+    metadata: &CordlMetadata,
+    only: Option<&HashSet<CsTypeTag>>,
+    folder: &Path,
+    split_namespaces: bool,
+) -> color_eyre::Result<OutputStats> {
+    let writer = CsDumpWriter {
+        metadata,
+        name_resolver: CsNameResolver {
+            cordl_metadata: metadata,
+            collection: cs_collection,
+        },
+    };
+
+    // nested types are written inside their declaring type
+    // and generic instantiations are left out, only the definitions are declared
+    let types = cs_collection
+        .sorted_types(only)
+        .into_iter()
+        .filter(|t| t.declaring_ty.is_none() && t.generic_instantiations_args_types.is_none())
+        .collect_vec();
+
+    let mut files: BTreeMap<String, String> = BTreeMap::new();
+    for ty in types {
+        let file_name = match split_namespaces {
+            true => match ty.namespace().as_str() {
+                "" => "GlobalNamespace.cs".to_string(),
+                namespace => format!("{namespace}.cs"),
+            },
+            false => "dump.cs".to_string(),
+        };
+
+        let out = files.entry(file_name).or_default();
+        writer.write_type(out, ty, 0)?;
+        out.push('\n');
+    }
+
+    info!("Writing {} C# files", files.len());
+    let output = OutputFiles::new(folder)?;
+    for (file_name, content) in &files {
+        output.write(&folder.join(file_name), content.as_bytes())?;
+    }
+
+    output.finish()
+}
@@ -0,0 +1,402 @@
+use std::fmt::Write;
+
+use brocolib::global_metadata::Il2CppTypeDefinition;
+use itertools::Itertools;
+
+use crate::{
+    data::type_resolver::ResolvedTypeData,
+    generate::{
+        cs_members::{
+            CSMethodFlags, CsAttributeValue, CsCustomAttribute, CsField, CsMethod, CsParam,
+            CsParamFlags,
+        },
+        cs_name_resolver::{cs_literal, primitive_keyword, CsNameResolver},
+        cs_type::CsType,
+        metadata::CordlMetadata,
+        type_extensions::{
+            FIELD_ATTRIBUTE_FIELD_ACCESS_MASK, METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK,
+            TYPE_ATTRIBUTE_ABSTRACT, TYPE_ATTRIBUTE_SEALED, TYPE_ATTRIBUTE_VISIBILITY_MASK,
+        },
+    },
+};
+
+const INDENT: &str = "    ";
+
+///
+/// Renders types as C# declarations with their layout and addresses in comments,
+/// in the style of Il2CppDumper's `dump.cs`
+///
+pub struct CsDumpWriter<'a, 'b> {
+    pub metadata: &'a CordlMetadata<'b>,
+    pub name_resolver: CsNameResolver<'a, 'b>,
+}
+
+/// Access of a method or field, the masks share their values
+fn member_access(flags: u16) -> &'static str {
+    match flags {
+        1 => "private ",
+        2 => "private protected ",
+        3 => "internal ",
+        4 => "protected ",
+        5 => "protected internal ",
+        6 => "public ",
+        _ => "",
+    }
+}
+
+fn type_access(flags: u32) -> &'static str {
+    match flags & TYPE_ATTRIBUTE_VISIBILITY_MASK {
+        1 | 2 => "public ",
+        3 => "private ",
+        4 => "protected ",
+        6 => "private protected ",
+        7 => "protected internal ",
+        _ => "internal ",
+    }
+}
+
+fn attribute_literal(value: &CsAttributeValue) -> String {
+    match value {
+        CsAttributeValue::Value(v) => cs_literal(v),
+        CsAttributeValue::Type(Some(t)) => format!("typeof({t})"),
+        CsAttributeValue::Type(None) => "null".to_string(),
+        CsAttributeValue::Enum { ty, value } => format!("({ty}){}", cs_literal(value)),
+        CsAttributeValue::Array(Some(elements)) => {
+            format!(
+                "new[] {{ {} }}",
+                elements.iter().map(attribute_literal).join(", ")
+            )
+        }
+        CsAttributeValue::Array(None) => "null".to_string(),
+    }
+}
+
+impl CsDumpWriter<'_, '_> {
+    fn type_def(&self, ty: &CsType) -> &Il2CppTypeDefinition {
+        &self.metadata.metadata.global_metadata.type_definitions[ty.self_tag.get_tdi()]
+    }
+
+    fn write_attributes(
+        &self,
+        out: &mut String,
+        indent: &str,
+        attributes: &[CsCustomAttribute],
+    ) -> std::fmt::Result {
+        for attribute in attributes {
+            let name = attribute
+                .name
+                .strip_suffix("Attribute")
+                .unwrap_or(&attribute.name);
+            let args = attribute
+                .args
+                .iter()
+                .map(attribute_literal)
+                .chain(
+                    attribute
+                        .named_args
+                        .iter()
+                        .map(|n| format!("{} = {}", n.name, attribute_literal(&n.value))),
+                )
+                .join(", ");
+
+            match args.is_empty() {
+                true => writeln!(out, "{indent}[{name}]")?,
+                false => writeln!(out, "{indent}[{name}({args})]")?,
+            }
+        }
+        Ok(())
+    }
+
+    ///
+    /// Writes `ty` and its nested types, indented by `depth` levels
+    ///
+    pub fn write_type(&self, out: &mut String, ty: &CsType, depth: usize) -> std::fmt::Result {
+        let indent = INDENT.repeat(depth);
+        let td = self.type_def(ty);
+
+        if depth == 0 {
+            writeln!(out, "// Namespace: {}", ty.namespace())?;
+        }
+        self.write_attributes(out, &indent, &ty.attributes)?;
+
+        let (kind, modifiers) = if ty.is_enum_type {
+            ("enum", "")
+        } else if ty.is_interface {
+            ("interface", "")
+        } else if ty.is_value_type {
+            ("struct", "")
+        } else {
+            let is_abstract = td.flags & TYPE_ATTRIBUTE_ABSTRACT != 0;
+            let is_sealed = td.flags & TYPE_ATTRIBUTE_SEALED != 0;
+            let modifiers = match (is_abstract, is_sealed) {
+                (true, true) => "static ",
+                (true, false) => "abstract ",
+                (false, true) => "sealed ",
+                (false, false) => "",
+            };
+            ("class", modifiers)
+        };
+
+        let generics = ty
+            .generic_template
+            .as_ref()
+            .map(|t| format!("<{}>", t.just_names().join(", ")))
+            .unwrap_or_default();
+
+        let bases = ty
+            .enum_backing_type
+            .filter(|_| ty.is_enum_type)
+            .map(|t| vec![primitive_keyword(t)])
+            .unwrap_or_else(|| {
+                let parent = ty
+                    .parent
+                    .as_ref()
+                    .filter(|_| !ty.is_value_type && !ty.is_enum_type)
+                    .map(|p| self.name_resolver.resolve_name(p))
+                    .filter(|p| p != "object");
+
+                parent
+                    .into_iter()
+                    .chain(
+                        ty.interfaces
+                            .iter()
+                            .map(|i| self.name_resolver.resolve_name(i)),
+                    )
+                    .collect_vec()
+            });
+        let bases = match bases.is_empty() {
+            true => String::new(),
+            false => format!(" : {}", bases.join(", ")),
+        };
+
+        let size = ty
+            .size_info
+            .as_ref()
+            .map(|s| format!(", Size: 0x{:X}", s.instance_size))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{indent}{}{modifiers}{kind} {}{generics}{bases} // TypeDefIndex: {}{size}",
+            type_access(td.flags),
+            ty.name(),
+            ty.self_tag.get_tdi().index()
+        )?;
+        writeln!(out, "{indent}{{")?;
+
+        let member_indent = INDENT.repeat(depth + 1);
+
+        if !ty.fields.is_empty() {
+            writeln!(out, "{member_indent}// Fields")?;
+            for field in &ty.fields {
+                self.write_field(out, &member_indent, td, field)?;
+            }
+        }
+
+        if !ty.properties.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "{member_indent}// Properties")?;
+            for property in &ty.properties {
+                self.write_attributes(out, &member_indent, &property.attributes)?;
+                let accessors = property
+                    .getter
+                    .iter()
+                    .map(|_| "get; ")
+                    .chain(property.setter.iter().map(|_| "set; "))
+                    .join("");
+                let modifier = if property.instance { "" } else { "static " };
+                let name = if property.indexable {
+                    "this[]"
+                } else {
+                    &property.name
+                };
+                writeln!(
+                    out,
+                    "{member_indent}{modifier}{} {name} {{ {accessors}}}",
+                    self.name_resolver.resolve_name(&property.prop_ty)
+                )?;
+            }
+        }
+
+        if !ty.events.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "{member_indent}// Events")?;
+            for event in &ty.events {
+                let modifier = if event.instance { "" } else { "static " };
+                writeln!(
+                    out,
+                    "{member_indent}{modifier}event {} {};",
+                    self.name_resolver.resolve_name(&event.event_ty),
+                    event.name
+                )?;
+            }
+        }
+
+        if !ty.methods.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "{member_indent}// Methods")?;
+            for method in &ty.methods {
+                self.write_method(out, &member_indent, ty, method)?;
+            }
+        }
+
+        let nested_types = ty
+            .nested_types
+            .iter()
+            .filter_map(|tag| self.name_resolver.collection.get_cs_type(*tag))
+            .sorted_by(|a, b| a.name().cmp(b.name()))
+            .collect_vec();
+        for nested in nested_types {
+            writeln!(out)?;
+            self.write_type(out, nested, depth + 1)?;
+        }
+
+        writeln!(out, "{indent}}}")?;
+        Ok(())
+    }
+
+    fn write_field(
+        &self,
+        out: &mut String,
+        indent: &str,
+        td: &Il2CppTypeDefinition,
+        field: &CsField,
+    ) -> std::fmt::Result {
+        let metadata = self.metadata.metadata;
+        let access = td
+            .fields(metadata)
+            .iter()
+            .find(|f| f.name(metadata) == field.name)
+            .and_then(|f| {
+                self.metadata
+                    .metadata_registration
+                    .types
+                    .get(f.type_index as usize)
+            })
+            .map(|t| member_access(t.attrs & FIELD_ATTRIBUTE_FIELD_ACCESS_MASK))
+            .unwrap_or_default();
+
+        let modifiers = match (field.is_const, field.instance, field.readonly) {
+            (true, _, _) => "const ",
+            (false, false, true) => "static readonly ",
+            (false, false, false) => "static ",
+            (false, true, true) => "readonly ",
+            (false, true, false) => "",
+        };
+        let value = match (&field.value, field.is_const) {
+            (Some(value), true) => format!(" = {}", cs_literal(value)),
+            _ => String::new(),
+        };
+        let offset = field
+            .offset
+            .map(|o| format!(" // 0x{o:X}"))
+            .unwrap_or_default();
+
+        self.write_attributes(out, indent, &field.attributes)?;
+        writeln!(
+            out,
+            "{indent}{access}{modifiers}{} {}{value};{offset}",
+            self.name_resolver.resolve_name(&field.field_ty),
+            field.name
+        )
+    }
+
+    fn param(&self, param: &CsParam) -> String {
+        // byref parameters are spelled by their modifier rather than their type
+        let (keyword, ty) = match &param.il2cpp_ty.data {
+            ResolvedTypeData::ByRef(inner) | ResolvedTypeData::ByRefConst(inner) => {
+                let keyword = if param.modifiers.contains(CsParamFlags::OUT) {
+                    "out "
+                } else if param.modifiers.contains(CsParamFlags::IN) {
+                    "in "
+                } else {
+                    "ref "
+                };
+                (keyword, self.name_resolver.resolve_name(inner))
+            }
+            _ => ("", self.name_resolver.resolve_name(&param.il2cpp_ty)),
+        };
+        let default = param
+            .def_value
+            .as_ref()
+            .map(|v| format!(" = {}", cs_literal(v)))
+            .unwrap_or_default();
+
+        format!("{keyword}{ty} {}{default}", param.name)
+    }
+
+    fn write_method(
+        &self,
+        out: &mut String,
+        indent: &str,
+        ty: &CsType,
+        method: &CsMethod,
+    ) -> std::fmt::Result {
+        let method_def = &self.metadata.metadata.global_metadata.methods[method.method_index];
+        let flags = &method.method_flags;
+
+        let mut info = vec![];
+        if let Some(addr) = method.method_data.address() {
+            info.push(format!("RVA: 0x{addr:X}"));
+        }
+        if let Some(size) = method
+            .method_data
+            .estimated_size
+            .filter(|s| *s != 0 && *s != usize::MAX)
+        {
+            info.push(format!("Size: 0x{size:X}"));
+        }
+        if let Some(slot) = method.method_data.slot {
+            info.push(format!("Slot: {slot}"));
+        }
+        if !info.is_empty() {
+            writeln!(out, "{indent}// {}", info.join(" "))?;
+        }
+        self.write_attributes(out, indent, &method.attributes)?;
+
+        let access = if ty.is_interface {
+            ""
+        } else {
+            member_access(method_def.flags & METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK)
+        };
+        let modifiers = if flags.contains(CSMethodFlags::STATIC) {
+            "static "
+        } else if ty.is_interface {
+            ""
+        } else if flags.contains(CSMethodFlags::ABSTRACT) {
+            "abstract "
+        } else if flags.contains(CSMethodFlags::OVERRIDE) {
+            match flags.contains(CSMethodFlags::FINAL) {
+                true => "sealed override ",
+                false => "override ",
+            }
+        } else if flags.contains(CSMethodFlags::VIRTUAL) && !flags.contains(CSMethodFlags::FINAL) {
+            "virtual "
+        } else {
+            ""
+        };
+
+        let generics = method
+            .template
+            .as_ref()
+            .filter(|t| !t.names.is_empty())
+            .map(|t| format!("<{}>", t.just_names().join(", ")))
+            .unwrap_or_default();
+        let params = method.parameters.iter().map(|p| self.param(p)).join(", ");
+        let body = match flags.contains(CSMethodFlags::ABSTRACT) || ty.is_interface {
+            true => ";",
+            false => " { }",
+        };
+
+        // constructors are named after the type and have no return type
+        let signature = match method.name.as_str() {
+            ".ctor" | ".cctor" => format!("{}({params})", ty.name()),
+            name => format!(
+                "{} {name}{generics}({params})",
+                self.name_resolver.resolve_name(&method.return_type)
+            ),
+        };
+
+        writeln!(out, "{indent}{access}{modifiers}{signature}{body}")?;
+        writeln!(out)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod csharp_main;
+
+mod csharp_writer;
@@ -0,0 +1,308 @@
+use std::io::{Cursor, Read};
+
+use brocolib::{
+    global_metadata::{MethodIndex, TypeDefinitionIndex},
+    runtime_metadata::TypeData,
+};
+use byteorder::ReadBytesExt;
+use color_eyre::eyre::{bail, ContextCompat};
+use log::warn;
+
+use crate::{helpers::cursor::ReadBytesExtensions, Endian};
+
+use super::{
+    cs_members::{
+        CsAttributeNamedArg, CsAttributeNamedArgKind, CsAttributeValue, CsCustomAttribute, CsValue,
+    },
+    cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata,
+};
+
+// Il2CppTypeEnum values used by the attribute blob encoding
+// See libil2cpp/vm/CustomAttributeDataReader.cpp
+const TYPE_BOOLEAN: u8 = 0x02;
+const TYPE_CHAR: u8 = 0x03;
+const TYPE_I1: u8 = 0x04;
+const TYPE_U1: u8 = 0x05;
+const TYPE_I2: u8 = 0x06;
+const TYPE_U2: u8 = 0x07;
+const TYPE_I4: u8 = 0x08;
+const TYPE_U4: u8 = 0x09;
+const TYPE_I8: u8 = 0x0a;
+const TYPE_U8: u8 = 0x0b;
+const TYPE_R4: u8 = 0x0c;
+const TYPE_R8: u8 = 0x0d;
+const TYPE_STRING: u8 = 0x0e;
+const TYPE_OBJECT: u8 = 0x1c;
+const TYPE_SZARRAY: u8 = 0x1d;
+const TYPE_ENUM: u8 = 0x55;
+/// System.Type, encoded as an index into the runtime type table
+const TYPE_IL2CPP_TYPE_INDEX: u8 = 0xff;
+
+///
+/// Decodes the custom attributes attached to `token`.
+/// `tdi` is the type declaring the member, used to find the image the token belongs to
+///
+/// Attributes that fail to decode are logged and skipped
+///
+pub fn get_custom_attributes(
+    metadata: &CordlMetadata,
+    tdi: TypeDefinitionIndex,
+    token: u32,
+) -> Vec<CsCustomAttribute> {
+    let Some(range) = metadata.custom_attribute_ranges.get(&tdi) else {
+        return vec![];
+    };
+
+    let gm = &metadata.metadata.global_metadata;
+    let data_ranges = gm.attribute_data_ranges.as_vec();
+    let image_ranges = &data_ranges[range.clone()];
+
+    // sorted by token within an image
+    let Ok(i) = image_ranges.binary_search_by_key(&token, |r| u32::from(r.token)) else {
+        return vec![];
+    };
+
+    let data = gm.attribute_data.as_vec();
+    let start = image_ranges[i].start_offset as usize;
+    let end = data_ranges
+        .get(range.start + i + 1)
+        .map(|r| r.start_offset as usize)
+        .unwrap_or(data.len());
+
+    match read_attributes(metadata, &data[start..end]) {
+        Ok(attributes) => attributes,
+        Err(e) => {
+            warn!("Unable to read custom attributes for token 0x{token:x}: {e}");
+            vec![]
+        }
+    }
+}
+
+fn read_attributes(
+    metadata: &CordlMetadata,
+    data: &[u8],
+) -> color_eyre::Result<Vec<CsCustomAttribute>> {
+    let mut cursor = Cursor::new(data);
+    let count = cursor.read_compressed_u32::<Endian>()? as usize;
+
+    // constructors are written first, followed by the arguments of each attribute
+    let ctors = (0..count)
+        .map(|_| cursor.read_u32::<Endian>().map(MethodIndex::new))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    ctors
+        .into_iter()
+        .map(|ctor| read_attribute(metadata, &mut cursor, ctor))
+        .collect()
+}
+
+fn read_attribute(
+    metadata: &CordlMetadata,
+    cursor: &mut Cursor<&[u8]>,
+    ctor: MethodIndex,
+) -> color_eyre::Result<CsCustomAttribute> {
+    let gm = &metadata.metadata.global_metadata;
+    let ctor_method = gm
+        .methods
+        .as_vec()
+        .get(ctor.index() as usize)
+        .with_context(|| format!("Invalid attribute constructor {ctor:?}"))?;
+
+    let attribute_tdi = ctor_method.declaring_type;
+    let attribute_td = &gm.type_definitions[attribute_tdi];
+
+    let arg_count = cursor.read_compressed_u32::<Endian>()?;
+    let field_count = cursor.read_compressed_u32::<Endian>()?;
+    let property_count = cursor.read_compressed_u32::<Endian>()?;
+
+    let args = (0..arg_count)
+        .map(|_| read_value(metadata, cursor))
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    let mut named_args = Vec::with_capacity((field_count + property_count) as usize);
+    for _ in 0..field_count {
+        let value = read_value(metadata, cursor)?;
+        let (member_tdi, index) = read_named_arg_index(cursor, attribute_tdi)?;
+        let member_td = &gm.type_definitions[member_tdi];
+
+        let field = member_td
+            .fields(metadata.metadata)
+            .get(index)
+            .with_context(|| format!("Invalid attribute field index {index}"))?;
+
+        named_args.push(CsAttributeNamedArg {
+            name: field.name(metadata.metadata).to_string(),
+            kind: CsAttributeNamedArgKind::Field,
+            value,
+        });
+    }
+    for _ in 0..property_count {
+        let value = read_value(metadata, cursor)?;
+        let (member_tdi, index) = read_named_arg_index(cursor, attribute_tdi)?;
+        let member_td = &gm.type_definitions[member_tdi];
+
+        let property = member_td
+            .properties(metadata.metadata)
+            .get(index)
+            .with_context(|| format!("Invalid attribute property index {index}"))?;
+
+        named_args.push(CsAttributeNamedArg {
+            name: property.name(metadata.metadata).to_string(),
+            kind: CsAttributeNamedArgKind::Property,
+            value,
+        });
+    }
+
+    Ok(CsCustomAttribute {
+        name: attribute_td.full_name(metadata.metadata, false),
+        ty: CsTypeTag::TypeDefinitionIndex(attribute_tdi),
+        ctor,
+        args,
+        named_args,
+    })
+}
+
+/// Named arguments may refer to members declared in a parent of the attribute
+/// in which case the index is negative and followed by the declaring type
+fn read_named_arg_index(
+    cursor: &mut Cursor<&[u8]>,
+    attribute_tdi: TypeDefinitionIndex,
+) -> color_eyre::Result<(TypeDefinitionIndex, usize)> {
+    let index = cursor.read_compressed_i32::<Endian>()?;
+    if index >= 0 {
+        return Ok((attribute_tdi, index as usize));
+    }
+
+    let declaring_tdi = TypeDefinitionIndex::new(cursor.read_compressed_u32::<Endian>()?);
+    Ok((declaring_tdi, -(index + 1) as usize))
+}
+
+/// Reads the type of a value, resolving enums to their underlying type
+/// Returns the enum type name as well
+fn read_encoded_type(
+    metadata: &CordlMetadata,
+    cursor: &mut Cursor<&[u8]>,
+) -> color_eyre::Result<(u8, Option<String>)> {
+    let ty = cursor.read_u8()?;
+    if ty != TYPE_ENUM {
+        return Ok((ty, None));
+    }
+
+    let type_index = cursor.read_compressed_i32::<Endian>()?;
+    let enum_ty = metadata
+        .metadata_registration
+        .types
+        .get(type_index as usize)
+        .with_context(|| format!("Invalid enum type index {type_index}"))?;
+
+    let TypeData::TypeDefinitionIndex(enum_tdi) = enum_ty.data else {
+        bail!("Enum type {type_index} is not a type definition");
+    };
+    let enum_td = &metadata.metadata.global_metadata.type_definitions[enum_tdi];
+    let element_ty = metadata
+        .metadata_registration
+        .types
+        .get(enum_td.element_type_index as usize)
+        .with_context(|| format!("Enum {enum_tdi:?} has no element type"))?;
+
+    Ok((
+        element_ty.ty as u8,
+        Some(enum_td.full_name(metadata.metadata, false)),
+    ))
+}
+
+fn read_value(
+    metadata: &CordlMetadata,
+    cursor: &mut Cursor<&[u8]>,
+) -> color_eyre::Result<CsAttributeValue> {
+    let (ty, enum_name) = read_encoded_type(metadata, cursor)?;
+    read_value_of(metadata, cursor, ty, enum_name)
+}
+
+fn read_value_of(
+    metadata: &CordlMetadata,
+    cursor: &mut Cursor<&[u8]>,
+    ty: u8,
+    enum_name: Option<String>,
+) -> color_eyre::Result<CsAttributeValue> {
+    let value = match ty {
+        TYPE_BOOLEAN => CsValue::Bool(cursor.read_u8()? != 0),
+        TYPE_I1 => CsValue::I8(cursor.read_i8()?),
+        TYPE_U1 => CsValue::U8(cursor.read_u8()?),
+        TYPE_I2 => CsValue::I16(cursor.read_i16::<Endian>()?),
+        TYPE_U2 => CsValue::U16(cursor.read_u16::<Endian>()?),
+        TYPE_I4 => CsValue::I32(cursor.read_compressed_i32::<Endian>()?),
+        TYPE_U4 => CsValue::U32(cursor.read_compressed_u32::<Endian>()?),
+        TYPE_I8 => CsValue::I64(cursor.read_i64::<Endian>()?),
+        TYPE_U8 => CsValue::U64(cursor.read_u64::<Endian>()?),
+        TYPE_R4 => CsValue::F32(cursor.read_f32::<Endian>()?),
+        TYPE_R8 => CsValue::F64(cursor.read_f64::<Endian>()?),
+        TYPE_CHAR => {
+            let res = String::from_utf16_lossy(&[cursor.read_u16::<Endian>()?])
+                .escape_default()
+                .to_string();
+
+            CsValue::Char(res)
+        }
+        TYPE_STRING => {
+            let len = cursor.read_compressed_i32::<Endian>()?;
+            if len == -1 {
+                CsValue::Null
+            } else {
+                let mut buf = vec![0u8; len as usize];
+                cursor.read_exact(buf.as_mut_slice())?;
+
+                CsValue::String(String::from_utf8(buf)?.escape_default().to_string())
+            }
+        }
+        // boxed value, the actual type precedes it
+        TYPE_OBJECT => return read_value(metadata, cursor),
+        TYPE_IL2CPP_TYPE_INDEX => {
+            let type_index = cursor.read_compressed_i32::<Endian>()?;
+            if type_index == -1 {
+                return Ok(CsAttributeValue::Type(None));
+            }
+
+            let name = metadata
+                .metadata_registration
+                .types
+                .get(type_index as usize)
+                .map(|t| match t.data {
+                    TypeData::TypeDefinitionIndex(tdi) => {
+                        metadata.metadata.global_metadata.type_definitions[tdi]
+                            .full_name(metadata.metadata, false)
+                    }
+                    _ => format!("{:?}", t.ty),
+                })
+                .with_context(|| format!("Invalid type index {type_index}"))?;
+
+            return Ok(CsAttributeValue::Type(Some(name)));
+        }
+        TYPE_SZARRAY => {
+            let len = cursor.read_compressed_i32::<Endian>()?;
+            if len == -1 {
+                return Ok(CsAttributeValue::Array(None));
+            }
+
+            let (element_ty, element_enum) = read_encoded_type(metadata, cursor)?;
+            // if set, each element is prefixed by its own type
+            let elements_are_different = cursor.read_u8()? == 1;
+
+            let elements = (0..len)
+                .map(|_| match elements_are_different {
+                    true => read_value(metadata, cursor),
+                    false => read_value_of(metadata, cursor, element_ty, element_enum.clone()),
+                })
+                .collect::<color_eyre::Result<Vec<_>>>()?;
+
+            return Ok(CsAttributeValue::Array(Some(elements)));
+        }
+        _ => bail!("Unsupported attribute value type 0x{ty:x}"),
+    };
+
+    Ok(match enum_name {
+        Some(ty) => CsAttributeValue::Enum { ty, value },
+        None => CsAttributeValue::Value(value),
+    })
+}
@@ -0,0 +1,211 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+    path::Path,
+};
+
+use itertools::Itertools;
+use log::info;
+
+use crate::generate::{
+    cs_context_collection::TypeContextCollection,
+    cs_name_resolver::CsNameResolver,
+    cs_type::CsType,
+    cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata,
+    output::{OutputFiles, OutputStats},
+};
+
+use super::docs_writer::{
+    definition_tag, escape, namespace_file_name, page, DocsWriter, SEARCH_SCRIPT, STYLE,
+};
+
+/// A namespace and the namespaces below it, by their last part
+#[derive(Default)]
+struct NamespaceNode<'a> {
+    namespace: Option<&'a str>,
+    type_count: usize,
+    children: BTreeMap<&'a str, NamespaceNode<'a>>,
+}
+
+impl<'a> NamespaceNode<'a> {
+    fn insert(&mut self, namespace: &'a str, type_count: usize) {
+        let node = namespace
+            .split('.')
+            .fold(self, |node, part| node.children.entry(part).or_default());
+        node.namespace = Some(namespace);
+        node.type_count = type_count;
+    }
+
+    fn write(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, r#"<ul class="tree">"#)?;
+        for (part, child) in &self.children {
+            let label = match child.namespace {
+                Some(namespace) => format!(
+                    r#"<a href="namespaces/{}.html">{}</a> ({})"#,
+                    escape(namespace_file_name(namespace)),
+                    escape(part),
+                    child.type_count
+                ),
+                None => escape(part),
+            };
+
+            match child.children.is_empty() {
+                true => writeln!(out, "<li>{label}</li>")?,
+                false => {
+                    writeln!(out, "<li><details><summary>{label}</summary>")?;
+                    child.write(out)?;
+                    writeln!(out, "</details></li>")?;
+                }
+            }
+        }
+        writeln!(out, "</ul>")
+    }
+}
+
+fn write_namespace_page(
+    writer: &DocsWriter,
+    namespace: &str,
+    types: &[&CsType],
+) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<h1>Namespace {}</h1>",
+        escape(namespace_file_name(namespace))
+    )?;
+    writeln!(out, "<table><tr><th>Type</th><th>Size</th></tr>")?;
+    for ty in types {
+        let size = ty
+            .size_info
+            .as_ref()
+            .map(|s| format!("0x{:X}", s.instance_size))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            r#"<tr><td class="signature">{}</td><td class="number">{size}</td></tr>"#,
+            writer.type_link(ty)
+        )?;
+    }
+    writeln!(out, "</table>")?;
+
+    Ok(page(namespace_file_name(namespace), &out))
+}
+
+fn write_index(namespaces: &BTreeMap<String, Vec<&CsType>>) -> Result<String, std::fmt::Error> {
+    let mut tree = NamespaceNode::default();
+    for (namespace, types) in namespaces {
+        tree.insert(namespace_file_name(namespace), types.len());
+    }
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Index</title>
+<link rel="stylesheet" href="style.css">
+</head>
+<body>
+<h1>Index</h1>
+<input id="search" type="search" placeholder="Search types" autofocus>
+<ul id="results"></ul>
+<h2>Namespaces</h2>"#
+    )?;
+    tree.write(&mut out)?;
+    writeln!(
+        out,
+        r#"<script src="search.js"></script>
+</body>
+</html>"#
+    )?;
+
+    Ok(out)
+}
+
+///
+/// Writes a static site documenting every type definition to `folder`,
+/// with a page per namespace and type and a search over type names
+///
+pub fn run_docs(
+    cs_collection: &TypeContextCollection,
+    metadata: &CordlMetadata,
+    only: Option<&HashSet<CsTypeTag>>,
+    folder: &Path,
+) -> color_eyre::Result<OutputStats> {
+    // generic instantiations link to the page of their definition
+    let types = cs_collection
+        .sorted_types(only)
+        .into_iter()
+        .filter(|t| t.generic_instantiations_args_types.is_none())
+        .collect_vec();
+
+    let mut subclasses: HashMap<CsTypeTag, Vec<CsTypeTag>> = HashMap::new();
+    for ty in &types {
+        if let Some(parent) = ty.parent.as_ref().and_then(definition_tag) {
+            subclasses.entry(parent).or_default().push(ty.self_tag);
+        }
+    }
+
+    let writer = DocsWriter {
+        name_resolver: CsNameResolver {
+            cordl_metadata: metadata,
+            collection: cs_collection,
+        },
+        collection: cs_collection,
+        documented: types.iter().map(|t| t.self_tag).collect(),
+        subclasses,
+    };
+
+    let mut namespaces: BTreeMap<String, Vec<&CsType>> = BTreeMap::new();
+    for ty in types.iter().filter(|t| t.declaring_ty.is_none()) {
+        namespaces.entry(ty.namespace()).or_default().push(ty);
+    }
+
+    info!(
+        "Writing {} type pages in {} namespaces",
+        types.len(),
+        namespaces.len()
+    );
+    let output = OutputFiles::new(folder)?;
+
+    for ty in &types {
+        let path = folder
+            .join("types")
+            .join(format!("{}.html", ty.self_tag.get_tdi().index()));
+        output.write(&path, writer.write_type_page(ty)?.as_bytes())?;
+    }
+
+    for (namespace, types) in &namespaces {
+        let path = folder
+            .join("namespaces")
+            .join(format!("{}.html", namespace_file_name(namespace)));
+        let content = write_namespace_page(&writer, namespace, types)?;
+        output.write(&path, content.as_bytes())?;
+    }
+
+    let search_index = types
+        .iter()
+        .map(|t| {
+            (
+                t.cs_name_components.combine_all(),
+                format!("types/{}.html", t.self_tag.get_tdi().index()),
+            )
+        })
+        .collect_vec();
+    let search = format!(
+        "const TYPES = {};\n{SEARCH_SCRIPT}",
+        serde_json::to_string(&search_index)?
+    );
+
+    output.write(
+        &folder.join("index.html"),
+        write_index(&namespaces)?.as_bytes(),
+    )?;
+    output.write(&folder.join("search.js"), search.as_bytes())?;
+    output.write(&folder.join("style.css"), STYLE.as_bytes())?;
+
+    output.finish()
+}
@@ -0,0 +1,385 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+use itertools::Itertools;
+
+use crate::{
+    data::type_resolver::{ResolvedType, ResolvedTypeData},
+    generate::{
+        cs_context_collection::TypeContextCollection,
+        cs_members::{CsField, CsMethod},
+        cs_name_resolver::{cs_literal, CsNameResolver},
+        cs_type::CsType,
+        cs_type_tag::CsTypeTag,
+    },
+};
+
+pub const STYLE: &str = r#"body { font-family: sans-serif; margin: 2em auto; max-width: 75em; color: #222; }
+a { color: #0550ae; text-decoration: none; }
+a:hover { text-decoration: underline; }
+code, .signature { font-family: monospace; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 2em; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+th { background: #f3f3f3; }
+td.number { font-family: monospace; white-space: nowrap; }
+ul.tree { list-style: none; padding-left: 1.2em; }
+nav { margin-bottom: 1em; }
+#search { width: 100%; font-size: 1.1em; padding: 0.4em; box-sizing: border-box; }
+"#;
+
+/// The search box of the index, `TYPES` is prepended by the generator
+pub const SEARCH_SCRIPT: &str = r#"
+const input = document.getElementById("search");
+const results = document.getElementById("results");
+input.addEventListener("input", () => {
+    const query = input.value.trim().toLowerCase();
+    results.replaceChildren();
+    if (query.length === 0) {
+        return;
+    }
+    for (const [name, href] of TYPES.filter(t => t[0].toLowerCase().includes(query)).slice(0, 200)) {
+        const link = document.createElement("a");
+        link.href = href;
+        link.textContent = name;
+        const item = document.createElement("li");
+        item.appendChild(link);
+        results.appendChild(item);
+    }
+});
+"#;
+
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Page of a type, relative to the pages of the types and namespaces
+pub fn type_href(tag: CsTypeTag) -> String {
+    format!("../types/{}.html", tag.get_tdi().index())
+}
+
+/// Page of a namespace, relative to the pages of the types and namespaces
+pub fn namespace_href(namespace: &str) -> String {
+    format!("../namespaces/{}.html", namespace_file_name(namespace))
+}
+
+pub fn namespace_file_name(namespace: &str) -> &str {
+    match namespace {
+        "" => "GlobalNamespace",
+        namespace => namespace,
+    }
+}
+
+/// A type name without its generic arity suffix e.g List`1
+fn display_name(name: &str) -> &str {
+    name.split('`').next().unwrap_or(name)
+}
+
+/// The type definition a resolved type is an instance of
+pub fn definition_tag(ty: &ResolvedType) -> Option<CsTypeTag> {
+    match &ty.data {
+        ResolvedTypeData::Type(tag) => Some(*tag),
+        ResolvedTypeData::GenericInst(inner, _) => definition_tag(inner),
+        _ => None,
+    }
+}
+
+pub fn page(title: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{}</title>
+<link rel="stylesheet" href="../style.css">
+</head>
+<body>
+<nav><a href="../index.html">Index</a></nav>
+{body}</body>
+</html>
+"#,
+        escape(title)
+    )
+}
+
+///
+/// Renders each type as an HTML page, linking every type with a page
+///
+pub struct DocsWriter<'a, 'b> {
+    pub name_resolver: CsNameResolver<'a, 'b>,
+    pub collection: &'a TypeContextCollection,
+    /// Types with a page
+    pub documented: HashSet<CsTypeTag>,
+    /// Types by the type definition they inherit from
+    pub subclasses: HashMap<CsTypeTag, Vec<CsTypeTag>>,
+}
+
+impl DocsWriter<'_, '_> {
+    fn documented_type(&self, tag: CsTypeTag) -> Option<&CsType> {
+        self.documented
+            .contains(&tag)
+            .then(|| self.collection.get_cs_type(tag))
+            .flatten()
+    }
+
+    /// A link to the page of `ty`, named like it is in C# source
+    pub fn type_link(&self, ty: &CsType) -> String {
+        let name = ty
+            .cs_name_components
+            .declaring_types
+            .iter()
+            .flatten()
+            .chain(std::iter::once(ty.name()))
+            .map(|n| display_name(n))
+            .join(".");
+
+        format!(
+            r#"<a href="{}" title="{}">{}</a>"#,
+            type_href(ty.self_tag),
+            escape(&ty.cs_name_components.combine_all()),
+            escape(&name)
+        )
+    }
+
+    /// `ty` as HTML, with a link on every part which has a page
+    pub fn resolve_html(&self, ty: &ResolvedType) -> String {
+        match &ty.data {
+            ResolvedTypeData::Array(inner) => format!("{}[]", self.resolve_html(inner)),
+            ResolvedTypeData::Ptr(inner) => format!("{}*", self.resolve_html(inner)),
+            ResolvedTypeData::ByRef(inner) => format!("ref {}", self.resolve_html(inner)),
+            ResolvedTypeData::ByRefConst(inner) => format!("in {}", self.resolve_html(inner)),
+            ResolvedTypeData::GenericInst(inner, args) => {
+                let args = args.iter().map(|(a, _)| self.resolve_html(a)).join(", ");
+                match definition_tag(inner).and_then(|tag| self.documented_type(tag)) {
+                    Some(definition) => format!("{}&lt;{args}&gt;", self.type_link(definition)),
+                    None => escape(&self.name_resolver.resolve_name(ty)),
+                }
+            }
+            ResolvedTypeData::Type(tag) => match self.documented_type(*tag) {
+                Some(definition) => self.type_link(definition),
+                None => escape(&self.name_resolver.resolve_name(ty)),
+            },
+            _ => escape(&self.name_resolver.resolve_name(ty)),
+        }
+    }
+
+    /// The types `ty` inherits from, starting with its parent
+    fn inheritance_chain(&self, ty: &CsType) -> Vec<String> {
+        let mut chain = vec![];
+        let mut parent = ty.parent.as_ref();
+        let mut seen = HashSet::new();
+
+        while let Some(parent_ty) = parent {
+            chain.push(self.resolve_html(parent_ty));
+
+            let definition = definition_tag(parent_ty)
+                .filter(|tag| seen.insert(*tag))
+                .and_then(|tag| self.collection.get_cs_type(tag));
+            parent = definition.and_then(|d| d.parent.as_ref());
+        }
+        chain
+    }
+
+    pub fn write_type_page(&self, ty: &CsType) -> Result<String, std::fmt::Error> {
+        let mut out = String::new();
+        let kind = if ty.is_enum_type {
+            "enum"
+        } else if ty.is_interface {
+            "interface"
+        } else if ty.is_value_type {
+            "struct"
+        } else {
+            "class"
+        };
+        let full_name = ty.cs_name_components.combine_all();
+
+        writeln!(out, "<h1>{kind} {}</h1>", escape(&full_name))?;
+        writeln!(out, "<dl>")?;
+        let namespace = ty.namespace();
+        writeln!(
+            out,
+            r#"<dt>Namespace</dt><dd><a href="{}">{}</a></dd>"#,
+            escape(&namespace_href(&namespace)),
+            escape(namespace_file_name(&namespace))
+        )?;
+        if let Some(declaring) = ty.declaring_ty.and_then(|tag| self.documented_type(tag)) {
+            writeln!(
+                out,
+                "<dt>Declared in</dt><dd>{}</dd>",
+                self.type_link(declaring)
+            )?;
+        }
+        writeln!(
+            out,
+            "<dt>TypeDefIndex</dt><dd>{}</dd>",
+            ty.self_tag.get_tdi().index()
+        )?;
+        if let Some(size_info) = &ty.size_info {
+            writeln!(out, "<dt>Size</dt><dd>0x{:X}</dd>", size_info.instance_size)?;
+        }
+
+        let chain = self.inheritance_chain(ty);
+        if !chain.is_empty() {
+            writeln!(
+                out,
+                r#"<dt>Inheritance</dt><dd class="signature">{} ← {}</dd>"#,
+                chain.iter().rev().join(" ← "),
+                escape(display_name(ty.name()))
+            )?;
+        }
+        if !ty.interfaces.is_empty() {
+            let interfaces = ty
+                .interfaces
+                .iter()
+                .map(|i| self.resolve_html(i))
+                .join(", ");
+            writeln!(
+                out,
+                r#"<dt>Implements</dt><dd class="signature">{interfaces}</dd>"#
+            )?;
+        }
+        if let Some(subclasses) = self.subclasses.get(&ty.self_tag) {
+            let subclasses = subclasses
+                .iter()
+                .filter_map(|tag| self.documented_type(*tag))
+                .map(|t| self.type_link(t))
+                .join(", ");
+            writeln!(
+                out,
+                r#"<dt>Known subclasses</dt><dd class="signature">{subclasses}</dd>"#
+            )?;
+        }
+        let nested_types = ty
+            .nested_types
+            .iter()
+            .filter_map(|tag| self.documented_type(*tag))
+            .sorted_by(|a, b| a.name().cmp(b.name()))
+            .map(|t| self.type_link(t))
+            .join(", ");
+        if !nested_types.is_empty() {
+            writeln!(
+                out,
+                r#"<dt>Nested types</dt><dd class="signature">{nested_types}</dd>"#
+            )?;
+        }
+        writeln!(out, "</dl>")?;
+
+        if !ty.fields.is_empty() {
+            writeln!(out, "<h2>Fields</h2>")?;
+            writeln!(
+                out,
+                "<table><tr><th>Field</th><th>Offset</th><th>Size</th></tr>"
+            )?;
+            for field in &ty.fields {
+                self.write_field(&mut out, field)?;
+            }
+            writeln!(out, "</table>")?;
+        }
+
+        if !ty.properties.is_empty() {
+            writeln!(out, "<h2>Properties</h2>")?;
+            writeln!(out, "<table><tr><th>Property</th></tr>")?;
+            for property in &ty.properties {
+                let modifier = if property.instance { "" } else { "static " };
+                let accessors = property
+                    .getter
+                    .iter()
+                    .map(|_| "get; ")
+                    .chain(property.setter.iter().map(|_| "set; "))
+                    .join("");
+                writeln!(
+                    out,
+                    r#"<tr><td class="signature">{modifier}{} {} {{ {accessors}}}</td></tr>"#,
+                    self.resolve_html(&property.prop_ty),
+                    escape(&property.name)
+                )?;
+            }
+            writeln!(out, "</table>")?;
+        }
+
+        if !ty.methods.is_empty() {
+            writeln!(out, "<h2>Methods</h2>")?;
+            writeln!(
+                out,
+                "<table><tr><th>Method</th><th>RVA</th><th>Size</th><th>Slot</th></tr>"
+            )?;
+            for method in &ty.methods {
+                self.write_method(&mut out, method)?;
+            }
+            writeln!(out, "</table>")?;
+        }
+
+        Ok(page(&full_name, &out))
+    }
+
+    fn write_field(&self, out: &mut String, field: &CsField) -> std::fmt::Result {
+        let modifiers = match (field.is_const, field.instance, field.readonly) {
+            (true, _, _) => "const ",
+            (false, false, true) => "static readonly ",
+            (false, false, false) => "static ",
+            (false, true, true) => "readonly ",
+            (false, true, false) => "",
+        };
+        let value = field
+            .value
+            .as_ref()
+            .filter(|_| field.is_const)
+            .map(|v| format!(" = {}", escape(&cs_literal(v))))
+            .unwrap_or_default();
+        let offset = field.offset.map(|o| format!("0x{o:X}")).unwrap_or_default();
+        let size = match field.instance {
+            true => format!("0x{:X}", field.size),
+            false => String::new(),
+        };
+
+        writeln!(
+            out,
+            r#"<tr><td class="signature">{modifiers}{} {}{value}</td><td class="number">{offset}</td><td class="number">{size}</td></tr>"#,
+            self.resolve_html(&field.field_ty),
+            escape(&field.name)
+        )
+    }
+
+    fn write_method(&self, out: &mut String, method: &CsMethod) -> std::fmt::Result {
+        let modifier = if method.instance { "" } else { "static " };
+        let generics = method
+            .template
+            .as_ref()
+            .filter(|t| !t.names.is_empty())
+            .map(|t| format!("&lt;{}&gt;", t.just_names().map(|n| escape(n)).join(", ")))
+            .unwrap_or_default();
+        let params = method
+            .parameters
+            .iter()
+            .map(|p| format!("{} {}", self.resolve_html(&p.il2cpp_ty), escape(&p.name)))
+            .join(", ");
+
+        let addr = method
+            .method_data
+            .address()
+            .map(|a| format!("0x{a:X}"))
+            .unwrap_or_default();
+        let size = method
+            .method_data
+            .estimated_size
+            .filter(|s| *s != 0 && *s != usize::MAX)
+            .map(|s| format!("0x{s:X}"))
+            .unwrap_or_default();
+        let slot = method
+            .method_data
+            .slot
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+
+        writeln!(
+            out,
+            r#"<tr><td class="signature">{modifier}{} {}{generics}({params})</td><td class="number">{addr}</td><td class="number">{size}</td><td class="number">{slot}</td></tr>"#,
+            self.resolve_html(&method.return_type),
+            escape(&method.name)
+        )
+    }
+}
@@ -0,0 +1,3 @@
+pub mod docs_main;
+
+mod docs_writer;
@@ -0,0 +1,211 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+    path::Path,
+};
+
+use itertools::Itertools;
+use log::info;
+
+use crate::generate::{
+    cs_context_collection::TypeContextCollection,
+    cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata,
+    output::{OutputFiles, OutputStats},
+};
+
+use super::{
+    frida_name_resolver::{ts_namespace, FridaNameResolver},
+    frida_writer::{FridaWriter, PRELUDE},
+};
+
+/// Typings of every declared type
+pub const TYPINGS_FILE: &str = "cordl.d.ts";
+/// Resolves methods to addresses in the running game
+pub const RUNTIME_FILE: &str = "cordl_runtime.ts";
+/// Checks both files with `tsc`, against the Frida typings
+pub const TSCONFIG_FILE: &str = "tsconfig.json";
+
+const TSCONFIG: &str = r#"{
+    "compilerOptions": {
+        "target": "es2020",
+        "lib": ["es2020"],
+        "strict": true,
+        "noEmit": true,
+        "types": ["frida-gum"]
+    },
+    "files": ["cordl.d.ts", "cordl_runtime.ts"]
+}
+"#;
+
+const RUNTIME: &str = r#"/** The module il2cpp compiled the game code into */
+export const IL2CPP_MODULE = Process.platform === "windows" ? "GameAssembly.dll" : "libil2cpp.so";
+
+/** MethodIndex, address relative to the il2cpp module, estimated size and name */
+type MethodEntry = [number, number, number, string];
+
+let base: NativePointer | null = null;
+let byIndex: Map<number, MethodEntry> | null = null;
+
+/** Where the il2cpp module is loaded */
+export function il2cppBase(): NativePointer {
+    if (base === null) {
+        base = Process.getModuleByName(IL2CPP_MODULE).base;
+    }
+    return base;
+}
+
+function method(methodIndex: number): MethodEntry {
+    if (byIndex === null) {
+        byIndex = new Map(METHODS.map(m => [m[0], m]));
+    }
+    const entry = byIndex.get(methodIndex);
+    if (entry === undefined) {
+        throw new Error(`MethodIndex ${methodIndex} has no address`);
+    }
+    return entry;
+}
+
+/** The address of a method in the running game */
+export function methodAddress(methodIndex: number): NativePointer {
+    return il2cppBase().add(method(methodIndex)[1]);
+}
+
+/** The full name of a method e.g `System.Object::ToString` */
+export function methodName(methodIndex: number): string {
+    return method(methodIndex)[3];
+}
+
+/**
+ * A callable method. Instance methods take the object as their first argument
+ * and every method takes a trailing MethodInfo pointer
+ */
+export function methodFunction(
+    methodIndex: number,
+    returnType: NativeFunctionReturnType,
+    argTypes: NativeFunctionArgumentType[],
+): NativeFunction<any, any[]> {
+    return new NativeFunction(methodAddress(methodIndex), returnType, argTypes);
+}
+
+export function hookMethod(methodIndex: number, callbacks: InvocationListenerCallbacks): InvocationListener {
+    return Interceptor.attach(methodAddress(methodIndex), callbacks);
+}
+
+/** The MethodIndex of the method containing `address`, e.g for backtraces */
+export function methodAt(address: NativePointer): number | null {
+    if (address.compare(il2cppBase()) < 0) {
+        return null;
+    }
+    const rva = address.sub(il2cppBase()).toUInt32();
+    let low = 0;
+    let high = METHODS.length - 1;
+    let found: MethodEntry | null = null;
+    while (low <= high) {
+        const mid = (low + high) >> 1;
+        if (METHODS[mid][1] <= rva) {
+            found = METHODS[mid];
+            low = mid + 1;
+        } else {
+            high = mid - 1;
+        }
+    }
+    if (found === null || (found[2] !== 0 && rva >= found[1] + found[2])) {
+        return null;
+    }
+    return found[0];
+}
+"#;
+
+///
+/// Writes TypeScript typings of every type definition for Frida scripts to `folder`,
+/// with a runtime module resolving their methods by MethodIndex
+///
+pub fn run_frida(
+    cs_collection: &TypeContextCollection,
+    metadata: &CordlMetadata,
+    only: Option<&HashSet<CsTypeTag>>,
+    folder: &Path,
+) -> color_eyre::Result<OutputStats> {
+    // generic instantiations are left out, only the definitions are declared
+    let types = cs_collection
+        .sorted_types(only)
+        .into_iter()
+        .filter(|t| t.generic_instantiations_args_types.is_none())
+        .collect_vec();
+
+    let writer = FridaWriter {
+        name_resolver: FridaNameResolver {
+            cordl_metadata: metadata,
+            collection: cs_collection,
+            declared: types.iter().map(|t| t.self_tag).collect(),
+        },
+    };
+
+    // nested types are written in a namespace merged with their declaring type
+    let mut namespaces: BTreeMap<String, String> = BTreeMap::new();
+    for ty in types.iter().filter(|t| t.declaring_ty.is_none()) {
+        let namespace = ts_namespace(ty.cs_name_components.namespace.as_deref());
+        let out = namespaces.entry(namespace).or_default();
+        writer.write_type(out, ty, 1)?;
+    }
+
+    let mut typings = String::new();
+    writeln!(typings, "// Generated by cordl, do not edit")?;
+    writeln!(typings)?;
+    typings.push_str(PRELUDE);
+    for (namespace, declarations) in &namespaces {
+        writeln!(typings)?;
+        writeln!(typings, "declare namespace {namespace} {{")?;
+        typings.push_str(declarations);
+        writeln!(typings, "}}")?;
+    }
+
+    let methods = types
+        .iter()
+        .flat_map(|t| t.methods.iter().map(move |m| (t, m)))
+        .filter_map(|(t, m)| {
+            let addr = m.method_data.address()?;
+            let size = m
+                .method_data
+                .estimated_size
+                .filter(|s| *s != usize::MAX)
+                .unwrap_or_default();
+            let name = format!("{}::{}", t.cs_name_components.combine_all(), m.name);
+
+            Some((addr, m.method_index.index(), size, name))
+        })
+        .sorted()
+        .collect_vec();
+
+    let mut runtime = String::new();
+    writeln!(runtime, "// Generated by cordl, do not edit")?;
+    writeln!(runtime)?;
+    runtime.push_str(RUNTIME);
+    writeln!(runtime)?;
+    writeln!(
+        runtime,
+        "// sorted by address for methodAt
+const METHODS: MethodEntry[] = ["
+    )?;
+    for (addr, method_index, size, name) in &methods {
+        writeln!(
+            runtime,
+            "    [{method_index}, 0x{addr:X}, 0x{size:X}, {}],",
+            serde_json::to_string(name)?
+        )?;
+    }
+    writeln!(runtime, "];")?;
+
+    info!(
+        "Writing {} types and {} method addresses",
+        types.len(),
+        methods.len()
+    );
+    let output = OutputFiles::new(folder)?;
+    output.write(&folder.join(TYPINGS_FILE), typings.as_bytes())?;
+    output.write(&folder.join(RUNTIME_FILE), runtime.as_bytes())?;
+    output.write(&folder.join(TSCONFIG_FILE), TSCONFIG.as_bytes())?;
+
+    output.finish()
+}
@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use brocolib::runtime_metadata::Il2CppTypeEnum;
+use itertools::Itertools;
+
+use crate::{
+    data::{
+        name_components::NameComponents,
+        type_resolver::{ResolvedType, ResolvedTypeData},
+    },
+    generate::{
+        cs_context_collection::TypeContextCollection, cs_type_tag::CsTypeTag,
+        metadata::CordlMetadata,
+    },
+};
+
+/// TypeScript namespace of types without a C# namespace
+pub const GLOBAL_NAMESPACE: &str = "GlobalNamespace";
+
+/// Stands in for types which are not declared, such as types left out by `--only`
+pub const UNKNOWN_TYPE: &str = "Cordl.Unknown";
+
+const RESERVED_WORDS: &[&str] = &[
+    "arguments",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+///
+/// Makes a C# name a valid TypeScript identifier,
+/// dropping the generic arity suffix e.g List`1
+///
+pub fn ts_ident(name: &str) -> String {
+    let name = name.split('`').next().unwrap_or(name);
+    let mut ident: String = name
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || c == '_' || c == '$' {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect();
+
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if RESERVED_WORDS.contains(&ident.as_str()) {
+        ident.push('_');
+    }
+    ident
+}
+
+/// The TypeScript namespace path of a C# namespace
+pub fn ts_namespace(namespace: Option<&str>) -> String {
+    match namespace {
+        None | Some("") => GLOBAL_NAMESPACE.to_string(),
+        Some(namespace) => namespace.split('.').map(ts_ident).join("."),
+    }
+}
+
+///
+/// Resolves types to their TypeScript spelling e.g `System.Collections.Generic.List<number>`
+/// Nested types live in a namespace merged with their declaring type
+///
+pub struct FridaNameResolver<'a, 'b> {
+    pub cordl_metadata: &'a CordlMetadata<'b>,
+    pub collection: &'a TypeContextCollection,
+    /// Types with a declaration in the typings
+    pub declared: HashSet<CsTypeTag>,
+}
+
+impl FridaNameResolver<'_, '_> {
+    /// The declared path of a type, without generics
+    pub fn type_path(&self, name: &NameComponents) -> String {
+        let mut path = ts_namespace(name.namespace.as_deref());
+        for declaring_type in name.declaring_types.iter().flatten() {
+            path.push('.');
+            path.push_str(&ts_ident(declaring_type));
+        }
+        path.push('.');
+        path.push_str(&ts_ident(&name.name));
+        path
+    }
+
+    pub fn resolve_name(&self, ty: &ResolvedType) -> String {
+        let metadata = self.cordl_metadata;
+        match &ty.data {
+            ResolvedTypeData::Array(array_type) => {
+                format!("Cordl.Array<{}>", self.resolve_name(array_type))
+            }
+            ResolvedTypeData::GenericInst(resolved_type, vec) => {
+                let type_def_name = self.resolve_name(resolved_type);
+                if type_def_name == UNKNOWN_TYPE {
+                    return type_def_name;
+                }
+
+                let generic_types_formatted =
+                    vec.iter().map(|(r, _inc)| self.resolve_name(r)).join(", ");
+
+                format!("{type_def_name}<{generic_types_formatted}>")
+            }
+            ResolvedTypeData::GenericArg(gen_param_idx, _)
+            | ResolvedTypeData::GenericMethodArg(_, gen_param_idx, _) => {
+                let generic_param =
+                    &metadata.metadata.global_metadata.generic_parameters[*gen_param_idx];
+
+                ts_ident(generic_param.name(metadata.metadata))
+            }
+            ResolvedTypeData::Ptr(resolved_type) => {
+                format!("Cordl.Ptr<{}>", self.resolve_name(resolved_type))
+            }
+            ResolvedTypeData::ByRef(resolved_type)
+            | ResolvedTypeData::ByRefConst(resolved_type) => {
+                format!("Cordl.Ref<{}>", self.resolve_name(resolved_type))
+            }
+            ResolvedTypeData::Type(resolved_tag) => match self.declared.contains(resolved_tag) {
+                true => self
+                    .collection
+                    .get_cs_type(*resolved_tag)
+                    .map(|t| self.type_path(&t.cs_name_components))
+                    .unwrap_or_else(|| UNKNOWN_TYPE.to_string()),
+                false => UNKNOWN_TYPE.to_string(),
+            },
+            ResolvedTypeData::Primitive(il2_cpp_type_enum) => {
+                primitive_ts_type(*il2_cpp_type_enum).to_string()
+            }
+            ResolvedTypeData::Blacklisted(_) => UNKNOWN_TYPE.to_string(),
+        }
+    }
+}
+
+/// How Frida represents a primitive
+fn primitive_ts_type(ty: Il2CppTypeEnum) -> &'static str {
+    match ty {
+        Il2CppTypeEnum::Void => "void",
+        Il2CppTypeEnum::Boolean => "boolean",
+        Il2CppTypeEnum::Char
+        | Il2CppTypeEnum::I1
+        | Il2CppTypeEnum::U1
+        | Il2CppTypeEnum::I2
+        | Il2CppTypeEnum::U2
+        | Il2CppTypeEnum::I4
+        | Il2CppTypeEnum::U4
+        | Il2CppTypeEnum::R4
+        | Il2CppTypeEnum::R8 => "number",
+        Il2CppTypeEnum::I8 => "Int64",
+        Il2CppTypeEnum::U8 => "UInt64",
+        Il2CppTypeEnum::I | Il2CppTypeEnum::U => "NativePointer",
+        Il2CppTypeEnum::String => "Cordl.String",
+        Il2CppTypeEnum::Object => "Cordl.Object",
+        _ => UNKNOWN_TYPE,
+    }
+}
@@ -0,0 +1,393 @@
+use std::{collections::HashSet, fmt::Write};
+
+use itertools::Itertools;
+
+use crate::{
+    data::type_resolver::{ResolvedType, ResolvedTypeData},
+    generate::{
+        cs_members::{CsField, CsMethod, CsValue},
+        cs_type::CsType,
+    },
+};
+
+use super::frida_name_resolver::{ts_ident, FridaNameResolver, UNKNOWN_TYPE};
+
+const INDENT: &str = "    ";
+
+/// Suppresses the error on the next line, where C# hides a member TypeScript requires to be compatible
+const TS_IGNORE: &str = "// @ts-ignore";
+
+/// Largest integer a TypeScript number literal holds exactly
+const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
+///
+/// Declarations shared by every type, for the values Frida hands scripts
+///
+pub const PRELUDE: &str = r#"declare namespace Cordl {
+    /** A managed object, the pointer to its il2cpp header */
+    class Object {
+        readonly handle: NativePointer;
+    }
+    class String extends Object {}
+    class Array<T> extends Object {
+        private readonly __element: T;
+    }
+    /** A pointer to a `T` */
+    type Ptr<T> = NativePointer & { readonly __pointee?: T };
+    /** A `ref`, `in` or `out` parameter to a `T` */
+    type Ref<T> = NativePointer & { readonly __referent?: T };
+    /** A type cordl did not declare */
+    type Unknown = NativePointer;
+}
+"#;
+
+///
+/// Renders types as TypeScript declarations for Frida scripts,
+/// with their layout and method indices in doc comments
+///
+pub struct FridaWriter<'a, 'b> {
+    pub name_resolver: FridaNameResolver<'a, 'b>,
+}
+
+/// A member name, quoted when C# allows it but TypeScript does not e.g `.ctor`
+fn member_name(name: &str) -> String {
+    let is_ident = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+    match is_ident {
+        true => name.to_string(),
+        false => format!("{name:?}"),
+    }
+}
+
+/// The integer value of an enum constant
+fn enum_value(value: &CsValue) -> Option<i128> {
+    match value {
+        CsValue::U8(x) => Some(*x as i128),
+        CsValue::U16(x) => Some(*x as i128),
+        CsValue::U32(x) => Some(*x as i128),
+        CsValue::U64(x) => Some(*x as i128),
+        CsValue::I8(x) => Some(*x as i128),
+        CsValue::I16(x) => Some(*x as i128),
+        CsValue::I32(x) => Some(*x as i128),
+        CsValue::I64(x) => Some(*x as i128),
+        _ => None,
+    }
+}
+
+fn ts_literal(value: &CsValue) -> Option<String> {
+    match value {
+        CsValue::String(s) | CsValue::Char(s) => Some(format!("{s:?}")),
+        CsValue::Bool(b) => Some(b.to_string()),
+        CsValue::F32(f) => Some(f.to_string()),
+        CsValue::F64(f) => Some(f.to_string()),
+        _ => enum_value(value).map(|v| v.to_string()),
+    }
+}
+
+fn generic_params<'n>(names: Option<impl Iterator<Item = &'n String>>) -> String {
+    names
+        .map(|names| format!("<{}>", names.map(|n| ts_ident(n)).join(", ")))
+        .filter(|g| g != "<>")
+        .unwrap_or_default()
+}
+
+/// Instance and static member names of `ty`, as they are declared
+fn member_names(ty: &CsType) -> (HashSet<String>, HashSet<String>) {
+    let mut instance_names = HashSet::new();
+    let mut static_names = HashSet::new();
+
+    let fields = ty.fields.iter().map(|f| (f.instance, &f.name));
+    let methods = ty.methods.iter().map(|m| (m.instance, &m.name));
+    for (instance, name) in fields.chain(methods) {
+        match instance {
+            true => instance_names.insert(member_name(name)),
+            false => static_names.insert(member_name(name)),
+        };
+    }
+
+    (instance_names, static_names)
+}
+
+impl FridaWriter<'_, '_> {
+    ///
+    /// Writes `ty`, followed by a namespace holding its nested types
+    ///
+    pub fn write_type(&self, out: &mut String, ty: &CsType, depth: usize) -> std::fmt::Result {
+        let indent = INDENT.repeat(depth);
+        let name = ts_ident(ty.name());
+
+        let mut info = vec![format!("TypeDefIndex: {}", ty.self_tag.get_tdi().index())];
+        if let Some(size_info) = &ty.size_info {
+            info.push(format!("Size: 0x{:X}", size_info.instance_size));
+        }
+        // classes only list their interfaces, C# implements them in ways `implements` rejects
+        // such as explicit implementations
+        let interfaces = self.interfaces(ty);
+        match ty.is_interface || interfaces.is_empty() {
+            true => writeln!(out, "{indent}/** {} */", info.join(", "))?,
+            false => {
+                writeln!(out, "{indent}/**")?;
+                writeln!(out, "{indent} * {}", info.join(", "))?;
+                writeln!(out, "{indent} * Implements: {}", interfaces.join(", "))?;
+                writeln!(out, "{indent} */")?;
+            }
+        }
+
+        if ty.is_enum_type {
+            self.write_enum(out, &indent, &name, ty)?;
+        } else {
+            self.write_class(out, &indent, &name, ty)?;
+        }
+
+        let nested_types = ty
+            .nested_types
+            .iter()
+            .filter(|tag| self.name_resolver.declared.contains(tag))
+            .filter_map(|tag| self.name_resolver.collection.get_cs_type(*tag))
+            .sorted_by(|a, b| a.name().cmp(b.name()))
+            .collect_vec();
+        if !nested_types.is_empty() {
+            writeln!(out, "{indent}namespace {name} {{")?;
+            for nested in nested_types {
+                self.write_type(out, nested, depth + 1)?;
+            }
+            writeln!(out, "{indent}}}")?;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Enums become a union of their values,
+    /// or `number` for `[Flags]` enums whose values are combined
+    ///
+    fn write_enum(
+        &self,
+        out: &mut String,
+        indent: &str,
+        name: &str,
+        ty: &CsType,
+    ) -> std::fmt::Result {
+        let values = ty
+            .fields
+            .iter()
+            .filter(|f| f.is_const)
+            .filter_map(|f| Some((f.name.as_str(), enum_value(f.value.as_ref()?)?)))
+            .collect_vec();
+
+        for (value_name, value) in &values {
+            writeln!(out, "{indent}/** {value_name} = {value} */")?;
+        }
+
+        let fits = values.iter().all(|(_, v)| v.abs() <= MAX_SAFE_INTEGER);
+        let union = match !values.is_empty() && !ty.is_flags_enum() && fits {
+            true => values.iter().map(|(_, v)| *v).unique().join(" | "),
+            false => "number".to_string(),
+        };
+        writeln!(out, "{indent}type {name} = {union};")
+    }
+
+    fn write_class(
+        &self,
+        out: &mut String,
+        indent: &str,
+        name: &str,
+        ty: &CsType,
+    ) -> std::fmt::Result {
+        let resolver = &self.name_resolver;
+        let generics = generic_params(ty.generic_template.as_ref().map(|t| t.just_names()));
+        let (instance_names, static_names) = member_names(ty);
+
+        let hidden_instance = if ty.is_interface {
+            let interfaces = self.interfaces(ty);
+            let extends = match interfaces.is_empty() {
+                true => String::new(),
+                false => format!(" extends {}", interfaces.join(", ")),
+            };
+            // TS2430 and TS2320 are reported on the interface name,
+            // for members hiding or conflicting between the extended interfaces
+            let inherited = ty
+                .interfaces
+                .iter()
+                .filter_map(|i| self.declared_type(i))
+                .map(|i| self.interface_members(i))
+                .collect_vec();
+            let conflicting = inherited
+                .iter()
+                .tuple_combinations()
+                .any(|(a, b)| !a.is_disjoint(b));
+            let hides = inherited.iter().any(|i| !i.is_disjoint(&instance_names));
+            if conflicting || hides {
+                writeln!(out, "{indent}{TS_IGNORE}")?;
+            }
+            writeln!(out, "{indent}interface {name}{generics}{extends} {{")?;
+
+            // interfaces are checked as a whole, on their name
+            HashSet::new()
+        } else {
+            // structs are declared without their System.ValueType parent,
+            // classes whose parent is not declared still extend Cordl.Object
+            let parent = ty
+                .parent
+                .as_ref()
+                .filter(|_| !ty.is_value_type)
+                .map(|p| match resolver.resolve_name(p) {
+                    p if p == UNKNOWN_TYPE => " extends Cordl.Object".to_string(),
+                    p => format!(" extends {p}"),
+                })
+                .unwrap_or_default();
+
+            let (ancestor_instance, ancestor_static) = self.ancestor_members(ty);
+            // TS2417 is reported on the class name, for statics hiding those of an ancestor
+            if !static_names.is_disjoint(&ancestor_static) {
+                writeln!(out, "{indent}{TS_IGNORE}")?;
+            }
+            writeln!(out, "{indent}class {name}{generics}{parent} {{")?;
+
+            &instance_names & &ancestor_instance
+        };
+
+        let member_indent = format!("{indent}{INDENT}");
+        // interfaces can't declare static members
+        let fields = ty.fields.iter().filter(|f| f.instance || !ty.is_interface);
+        for field in fields {
+            // TS2416 is reported on each member hiding an ancestor member of another type
+            if field.instance && hidden_instance.contains(&member_name(&field.name)) {
+                writeln!(out, "{member_indent}{TS_IGNORE}")?;
+            }
+            self.write_field(out, &member_indent, field)?;
+        }
+
+        let methods = ty.methods.iter().filter(|m| m.instance || !ty.is_interface);
+        for method in methods {
+            if method.instance && hidden_instance.contains(&member_name(&method.name)) {
+                writeln!(out, "{member_indent}{TS_IGNORE}")?;
+            }
+            self.write_method(out, &member_indent, method)?;
+        }
+
+        writeln!(out, "{indent}}}")
+    }
+
+    /// The declared interfaces of `ty`, Cordl.Unknown is a pointer rather than an interface
+    fn interfaces(&self, ty: &CsType) -> Vec<String> {
+        ty.interfaces
+            .iter()
+            .map(|i| self.name_resolver.resolve_name(i))
+            .filter(|i| i != UNKNOWN_TYPE)
+            .collect_vec()
+    }
+
+    /// The declaration of a type or of the definition of a generic instantiation
+    fn declared_type(&self, ty: &ResolvedType) -> Option<&CsType> {
+        let tag = match &ty.data {
+            ResolvedTypeData::Type(tag) => tag,
+            ResolvedTypeData::GenericInst(inner, _) => match &inner.data {
+                ResolvedTypeData::Type(tag) => tag,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        if !self.name_resolver.declared.contains(tag) {
+            return None;
+        }
+        self.name_resolver.collection.get_cs_type(*tag)
+    }
+
+    ///
+    /// Instance and static member names declared by the classes `ty` extends,
+    /// including the `handle` of Cordl.Object
+    ///
+    fn ancestor_members(&self, ty: &CsType) -> (HashSet<String>, HashSet<String>) {
+        let mut instance_names = HashSet::new();
+        let mut static_names = HashSet::new();
+
+        let mut parent = ty.parent.as_ref().filter(|_| !ty.is_value_type);
+        while let Some(p) = parent {
+            let Some(ancestor) = self.declared_type(p) else {
+                // undeclared parents are written as Cordl.Object
+                instance_names.insert("handle".to_string());
+                break;
+            };
+
+            let (ancestor_instance, ancestor_static) = member_names(ancestor);
+            instance_names.extend(ancestor_instance);
+            static_names.extend(ancestor_static);
+            parent = ancestor.parent.as_ref();
+        }
+
+        (instance_names, static_names)
+    }
+
+    /// Instance member names of the interface `ty` and of every interface it extends
+    fn interface_members(&self, ty: &CsType) -> HashSet<String> {
+        let mut names = member_names(ty).0;
+        for interface in ty.interfaces.iter().filter_map(|i| self.declared_type(i)) {
+            names.extend(self.interface_members(interface));
+        }
+        names
+    }
+
+    fn write_field(&self, out: &mut String, indent: &str, field: &CsField) -> std::fmt::Result {
+        let mut info = vec![];
+        if let Some(offset) = field.offset {
+            info.push(format!("Offset: 0x{offset:X}"));
+        }
+        if field.instance {
+            info.push(format!("Size: 0x{:X}", field.size));
+        }
+        if let Some(value) = field.value.as_ref().filter(|_| field.is_const) {
+            info.extend(ts_literal(value).map(|v| format!("= {v}")));
+        }
+        if !info.is_empty() {
+            writeln!(out, "{indent}/** {} */", info.join(", "))?;
+        }
+
+        let modifiers = match (field.instance, field.readonly || field.is_const) {
+            (true, true) => "readonly ",
+            (true, false) => "",
+            (false, true) => "static readonly ",
+            (false, false) => "static ",
+        };
+        writeln!(
+            out,
+            "{indent}{modifiers}{}: {};",
+            member_name(&field.name),
+            self.name_resolver.resolve_name(&field.field_ty)
+        )
+    }
+
+    fn write_method(&self, out: &mut String, indent: &str, method: &CsMethod) -> std::fmt::Result {
+        let mut info = vec![format!("MethodIndex: {}", method.method_index.index())];
+        if let Some(addr) = method.method_data.address() {
+            info.push(format!("RVA: 0x{addr:X}"));
+        }
+        writeln!(out, "{indent}/** {} */", info.join(", "))?;
+
+        let modifier = if method.instance { "" } else { "static " };
+        let generics = generic_params(method.template.as_ref().map(|t| t.just_names()));
+        let params = method
+            .parameters
+            .iter()
+            .map(|p| {
+                format!(
+                    "{}: {}",
+                    ts_ident(&p.name),
+                    self.name_resolver.resolve_name(&p.il2cpp_ty)
+                )
+            })
+            .join(", ");
+
+        writeln!(
+            out,
+            "{indent}{modifier}{}{generics}({params}): {};",
+            member_name(&method.name),
+            self.name_resolver.resolve_name(&method.return_type)
+        )
+    }
+}
@@ -0,0 +1,4 @@
+pub mod frida_main;
+
+mod frida_name_resolver;
+mod frida_writer;
@@ -0,0 +1,213 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+use itertools::Itertools;
+
+use crate::{
+    data::type_resolver::{ResolvedType, ResolvedTypeData},
+    generate::{
+        cs_context_collection::TypeContextCollection, cs_type::CsType, cs_type_tag::CsTypeTag,
+        metadata::CordlMetadata,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, clap::ValueEnum)]
+pub enum EdgeKind {
+    /// A type to its parent
+    Inherit,
+    /// A type to the interfaces it implements
+    Implement,
+    /// A type to the types of its fields
+    FieldUses,
+    /// A type to the types in its method signatures
+    MethodUses,
+    /// A declaring type to its nested types
+    Nests,
+    /// A type to everything it forward declares or includes in the C++ output
+    Depends,
+}
+
+impl EdgeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            EdgeKind::Inherit => "inherit",
+            EdgeKind::Implement => "implement",
+            EdgeKind::FieldUses => "field-uses",
+            EdgeKind::MethodUses => "method-uses",
+            EdgeKind::Nests => "nests",
+            EdgeKind::Depends => "depends",
+        }
+    }
+}
+
+pub struct GraphNode {
+    pub tag: CsTypeTag,
+    pub namespace: String,
+    /// The name in its namespace, with its declaring types
+    pub name: String,
+}
+
+impl GraphNode {
+    pub fn id(&self) -> String {
+        format!("t{}", self.tag.get_tdi().index())
+    }
+
+    pub fn full_name(&self) -> String {
+        match self.namespace.is_empty() {
+            true => self.name.clone(),
+            false => format!("{}.{}", self.namespace, self.name),
+        }
+    }
+}
+
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    /// Source, target and kind, between nodes of the graph
+    pub edges: BTreeSet<(CsTypeTag, CsTypeTag, EdgeKind)>,
+}
+
+/// Generic instantiations are shown as their definition
+fn definition(tag: CsTypeTag) -> CsTypeTag {
+    CsTypeTag::TypeDefinitionIndex(tag.get_tdi())
+}
+
+/// Every type definition named by `ty`, including generic arguments and element types
+fn collect_tags(ty: &ResolvedType, tags: &mut Vec<CsTypeTag>) {
+    match &ty.data {
+        ResolvedTypeData::Type(tag) => tags.push(definition(*tag)),
+        ResolvedTypeData::GenericInst(inner, args) => {
+            collect_tags(inner, tags);
+            for (arg, _) in args {
+                collect_tags(arg, tags);
+            }
+        }
+        ResolvedTypeData::Array(inner)
+        | ResolvedTypeData::Ptr(inner)
+        | ResolvedTypeData::ByRef(inner)
+        | ResolvedTypeData::ByRefConst(inner) => collect_tags(inner, tags),
+        ResolvedTypeData::Primitive(_)
+        | ResolvedTypeData::GenericArg(_, _)
+        | ResolvedTypeData::GenericMethodArg(_, _, _)
+        | ResolvedTypeData::Blacklisted(_) => {}
+    }
+}
+
+fn edges_of(ty: &CsType, kind: EdgeKind) -> Vec<CsTypeTag> {
+    let mut tags = vec![];
+    match kind {
+        EdgeKind::Inherit => {
+            if let Some(parent) = &ty.parent {
+                collect_tags(parent, &mut tags);
+            }
+        }
+        EdgeKind::Implement => {
+            for interface in &ty.interfaces {
+                collect_tags(interface, &mut tags);
+            }
+        }
+        EdgeKind::FieldUses => {
+            for field in &ty.fields {
+                collect_tags(&field.field_ty, &mut tags);
+            }
+        }
+        EdgeKind::MethodUses => {
+            for method in &ty.methods {
+                collect_tags(&method.return_type, &mut tags);
+                for param in &method.parameters {
+                    collect_tags(&param.il2cpp_ty, &mut tags);
+                }
+            }
+        }
+        EdgeKind::Nests => tags.extend(ty.nested_types.iter().copied().map(definition)),
+        EdgeKind::Depends => tags.extend(
+            ty.requirements
+                .depending_types
+                .iter()
+                .copied()
+                .map(definition),
+        ),
+    }
+    tags
+}
+
+fn make_node(
+    tag: CsTypeTag,
+    metadata: &CordlMetadata,
+    collection: &TypeContextCollection,
+) -> GraphNode {
+    match collection.get_cs_type(tag) {
+        Some(ty) => {
+            let name = ty
+                .cs_name_components
+                .declaring_types
+                .iter()
+                .flatten()
+                .chain(std::iter::once(ty.name()))
+                .join("/");
+
+            GraphNode {
+                tag,
+                namespace: ty.namespace(),
+                name,
+            }
+        }
+        // blacklisted types have no CsType
+        None => {
+            let td = &metadata.metadata.global_metadata.type_definitions[tag.get_tdi()];
+            GraphNode {
+                tag,
+                namespace: td.namespace(metadata.metadata).to_string(),
+                name: td.name(metadata.metadata).to_string(),
+            }
+        }
+    }
+}
+
+///
+/// Walks the `kinds` edges from `roots`, up to `depth` edges away.
+/// Types past the depth limit are left out along with their edges
+///
+pub fn make_graph(
+    metadata: &CordlMetadata,
+    collection: &TypeContextCollection,
+    roots: &[CsTypeTag],
+    kinds: &[EdgeKind],
+    depth: Option<usize>,
+) -> Graph {
+    let mut visited: HashSet<CsTypeTag> = HashSet::new();
+    let mut queue: VecDeque<(CsTypeTag, usize)> = VecDeque::new();
+    for root in roots.iter().copied().map(definition) {
+        if visited.insert(root) {
+            queue.push_back((root, 0));
+        }
+    }
+
+    let mut edges = BTreeSet::new();
+    while let Some((tag, distance)) = queue.pop_front() {
+        if depth.is_some_and(|depth| distance >= depth) {
+            continue;
+        }
+        let Some(ty) = collection.get_cs_type(tag) else {
+            continue;
+        };
+
+        for &kind in kinds {
+            for target in edges_of(ty, kind) {
+                if target == tag {
+                    continue;
+                }
+                edges.insert((tag, target, kind));
+                if visited.insert(target) {
+                    queue.push_back((target, distance + 1));
+                }
+            }
+        }
+    }
+
+    let nodes = visited
+        .into_iter()
+        .map(|tag| make_node(tag, metadata, collection))
+        .sorted_by(|a, b| a.full_name().cmp(&b.full_name()))
+        .collect_vec();
+
+    Graph { nodes, edges }
+}
@@ -0,0 +1,226 @@
+use std::{collections::BTreeMap, fmt::Write as _, fs, path::Path};
+
+use itertools::Itertools;
+use log::info;
+
+use crate::generate::{
+    cs_context_collection::TypeContextCollection, cs_type_tag::CsTypeTag, metadata::CordlMetadata,
+};
+
+use super::graph_data::{make_graph, Graph, GraphNode};
+
+pub use super::graph_data::EdgeKind;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz, render with e.g `dot -Tsvg`
+    Dot,
+    /// For yEd, Gephi and other graph editors
+    #[value(name = "graphml")]
+    GraphML,
+}
+
+pub struct GraphOptions {
+    pub format: GraphFormat,
+    pub edges: Vec<EdgeKind>,
+    /// How many edges away from the roots types are included, unlimited if `None`
+    pub depth: Option<usize>,
+    /// Group the types by namespace
+    pub cluster_namespaces: bool,
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn dot_edge_style(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::Inherit => "arrowhead=empty, style=bold",
+        EdgeKind::Implement => "arrowhead=empty, style=dashed",
+        EdgeKind::FieldUses => "color=\"#1f6feb\"",
+        EdgeKind::MethodUses => "color=\"#8250df\", style=dotted",
+        EdgeKind::Nests => "arrowtail=diamond, dir=back, color=\"#57606a\"",
+        EdgeKind::Depends => "color=\"#bf8700\"",
+    }
+}
+
+/// Nodes by namespace, or all in one group when not clustering
+fn group_nodes(graph: &Graph, cluster_namespaces: bool) -> BTreeMap<&str, Vec<&GraphNode>> {
+    let mut groups: BTreeMap<&str, Vec<&GraphNode>> = BTreeMap::new();
+    for node in &graph.nodes {
+        let group = match cluster_namespaces {
+            true => node.namespace.as_str(),
+            false => "",
+        };
+        groups.entry(group).or_default().push(node);
+    }
+    groups
+}
+
+fn write_dot(graph: &Graph, cluster_namespaces: bool) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, "digraph cordl {{")?;
+    writeln!(out, "    rankdir=LR;")?;
+    writeln!(out, "    node [shape=box, fontname=\"monospace\"];")?;
+
+    for (namespace, nodes) in group_nodes(graph, cluster_namespaces) {
+        let indent = match cluster_namespaces {
+            true => {
+                let label = match namespace {
+                    "" => "GlobalNamespace",
+                    namespace => namespace,
+                };
+                writeln!(out, "    subgraph \"cluster_{}\" {{", dot_escape(label))?;
+                writeln!(out, "        label=\"{}\";", dot_escape(label))?;
+                "        "
+            }
+            false => "    ",
+        };
+
+        for node in nodes {
+            // the namespace is shown by the cluster
+            let label = match cluster_namespaces {
+                true => node.name.clone(),
+                false => node.full_name(),
+            };
+            writeln!(
+                out,
+                "{indent}{} [label=\"{}\"];",
+                node.id(),
+                dot_escape(&label)
+            )?;
+        }
+
+        if cluster_namespaces {
+            writeln!(out, "    }}")?;
+        }
+    }
+
+    for (source, target, kind) in &graph.edges {
+        writeln!(
+            out,
+            "    t{} -> t{} [label=\"{}\", {}];",
+            source.get_tdi().index(),
+            target.get_tdi().index(),
+            kind.name(),
+            dot_edge_style(*kind)
+        )?;
+    }
+
+    writeln!(out, "}}")?;
+    Ok(out)
+}
+
+fn write_graphml(graph: &Graph, cluster_namespaces: bool) -> Result<String, std::fmt::Error> {
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="name" for="node" attr.name="name" attr.type="string"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="namespace" for="node" attr.name="namespace" attr.type="string"/>"#
+    )?;
+    writeln!(
+        out,
+        r#"  <key id="kind" for="edge" attr.name="kind" attr.type="string"/>"#
+    )?;
+    writeln!(out, r#"  <graph id="cordl" edgedefault="directed">"#)?;
+
+    // namespaces become group nodes holding a nested graph of their types
+    for (i, (namespace, nodes)) in group_nodes(graph, cluster_namespaces)
+        .into_iter()
+        .enumerate()
+    {
+        let indent = match cluster_namespaces {
+            true => {
+                writeln!(out, r#"    <node id="ns{i}">"#)?;
+                writeln!(
+                    out,
+                    r#"      <data key="namespace">{}</data>"#,
+                    xml_escape(namespace)
+                )?;
+                writeln!(out, r#"      <graph id="ns{i}:" edgedefault="directed">"#)?;
+                "        "
+            }
+            false => "    ",
+        };
+
+        for node in nodes {
+            writeln!(out, r#"{indent}<node id="{}">"#, node.id())?;
+            writeln!(
+                out,
+                r#"{indent}  <data key="name">{}</data>"#,
+                xml_escape(&node.name)
+            )?;
+            writeln!(
+                out,
+                r#"{indent}  <data key="namespace">{}</data>"#,
+                xml_escape(&node.namespace)
+            )?;
+            writeln!(out, "{indent}</node>")?;
+        }
+
+        if cluster_namespaces {
+            writeln!(out, "      </graph>")?;
+            writeln!(out, "    </node>")?;
+        }
+    }
+
+    for (source, target, kind) in &graph.edges {
+        writeln!(
+            out,
+            r#"    <edge source="t{}" target="t{}"><data key="kind">{}</data></edge>"#,
+            source.get_tdi().index(),
+            target.get_tdi().index(),
+            kind.name()
+        )?;
+    }
+
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")?;
+    Ok(out)
+}
+
+///
+/// Writes the graph of the types reachable from `roots` to `output`, or stdout
+///
+pub fn run_graph(
+    cs_collection: &TypeContextCollection,
+    metadata: &CordlMetadata,
+    roots: &[CsTypeTag],
+    options: &GraphOptions,
+    output: Option<&Path>,
+) -> color_eyre::Result<()> {
+    let edges = options.edges.iter().copied().unique().collect_vec();
+    let graph = make_graph(metadata, cs_collection, roots, &edges, options.depth);
+    info!(
+        "Graph of {} types and {} edges from {} roots",
+        graph.nodes.len(),
+        graph.edges.len(),
+        roots.len()
+    );
+
+    let content = match options.format {
+        GraphFormat::Dot => write_dot(&graph, options.cluster_namespaces)?,
+        GraphFormat::GraphML => write_graphml(&graph, options.cluster_namespaces)?,
+    };
+
+    match output {
+        Some(output) => fs::write(output, content)?,
+        None => print!("{content}"),
+    }
+    Ok(())
+}
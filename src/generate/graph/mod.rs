@@ -0,0 +1,3 @@
+pub mod graph_main;
+
+mod graph_data;
@@ -1,4 +1,5 @@
 use brocolib::{global_metadata::TypeDefinitionIndex, runtime_metadata::Il2CppTypeEnum};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -6,13 +7,15 @@ use crate::{
     generate::cs_type_tag::CsTypeTag,
 };
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, PartialOrd, Ord, Eq, Hash)]
+#[derive(
+    Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, PartialOrd, Ord, Eq, Hash,
+)]
 pub enum JsonTypeTag {
     TypeDefinition(u32),
     GenericInstantiation { type_definition: u32, inst: usize },
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 pub enum JsonResolvedTypeData {
     Array(Box<JsonResolvedTypeData>),
     GenericInst(Box<JsonResolvedTypeData>, Vec<(JsonResolvedTypeData, bool)>),
@@ -58,7 +61,7 @@ impl From<ResolvedType> for JsonResolvedTypeData {
 /// See ECMA-335, II.23.1.16
 ///
 /// Defined at `il2cpp-blob.h:6`
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, JsonSchema)]
 pub enum JsonTypeEnum {
     /// End of list
     End,
@@ -0,0 +1,378 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display},
+};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::json_gen::{JsonField, JsonMethod, JsonProperty, JsonType};
+
+///
+/// API differences between two generated type sets, usually two versions of a game.
+/// Types and members are matched by name since indices are not stable across versions
+///
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JsonApiDiff {
+    pub added_types: Vec<String>,
+    pub removed_types: Vec<String>,
+    pub changed_types: Vec<JsonTypeDiff>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonChange<T> {
+    pub old: T,
+    pub new: T,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonMembersDiff<T> {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonTypeDiff {
+    pub full_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<JsonChange<u32>>,
+    pub fields: JsonMembersDiff<JsonFieldDiff>,
+    pub methods: JsonMembersDiff<JsonMethodDiff>,
+    pub properties: JsonMembersDiff<JsonPropertyDiff>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonFieldDiff {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ty: Option<JsonChange<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<JsonChange<Option<u32>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<JsonChange<usize>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonMethodDiff {
+    /// Signature in the new version
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<JsonChange<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addrs: Option<JsonChange<Option<u64>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonPropertyDiff {
+    pub name: String,
+    pub ty: JsonChange<String>,
+}
+
+impl<T: PartialEq> JsonChange<T> {
+    fn make(old: T, new: T) -> Option<Self> {
+        (old != new).then_some(JsonChange { old, new })
+    }
+}
+
+impl<T> JsonMembersDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl JsonTypeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.size.is_none()
+            && self.fields.is_empty()
+            && self.methods.is_empty()
+            && self.properties.is_empty()
+    }
+}
+
+/// Nested types are stored as children, flatten them so they are matched by full name too
+fn flatten_types(types: &[JsonType]) -> BTreeMap<&str, &JsonType> {
+    fn visit<'a>(ty: &'a JsonType, map: &mut BTreeMap<&'a str, &'a JsonType>) {
+        map.insert(&ty.full_name, ty);
+        ty.children.iter().for_each(|c| visit(c, map));
+    }
+
+    let mut map = BTreeMap::new();
+    types.iter().for_each(|t| visit(t, &mut map));
+    map
+}
+
+/// Members are keyed by name, added and removed members are reported by key
+/// `diff_member` returns `None` if the member is unchanged
+fn diff_members<'a, M, D>(
+    old: impl IntoIterator<Item = (String, &'a M)>,
+    new: impl IntoIterator<Item = (String, &'a M)>,
+    diff_member: impl Fn(&str, &M, &M) -> Option<D>,
+) -> JsonMembersDiff<D>
+where
+    M: 'a,
+{
+    let old: BTreeMap<String, &M> = old.into_iter().collect();
+    let new: BTreeMap<String, &M> = new.into_iter().collect();
+
+    JsonMembersDiff {
+        added: new
+            .keys()
+            .filter(|k| !old.contains_key(*k))
+            .cloned()
+            .collect(),
+        removed: old
+            .keys()
+            .filter(|k| !new.contains_key(*k))
+            .cloned()
+            .collect(),
+        changed: new
+            .iter()
+            .filter_map(|(k, n)| old.get(k).and_then(|o| diff_member(k, o, n)))
+            .collect(),
+    }
+}
+
+fn method_key(method: &JsonMethod) -> String {
+    let params = method.parameters.iter().map(|p| &p.ty).join(", ");
+    format!("{}({params})", method.name)
+}
+
+fn method_signature(method: &JsonMethod) -> String {
+    let params = method
+        .parameters
+        .iter()
+        .map(|p| format!("{} {}", p.ty, p.name))
+        .join(", ");
+    let static_prefix = if method.instance { "" } else { "static " };
+
+    format!("{static_prefix}{} {}({params})", method.ret, method.name)
+}
+
+fn diff_methods(old: &[JsonMethod], new: &[JsonMethod]) -> JsonMembersDiff<JsonMethodDiff> {
+    let diff_method = |key: &str, o: &JsonMethod, n: &JsonMethod| {
+        let diff = JsonMethodDiff {
+            name: key.to_string(),
+            signature: JsonChange::make(method_signature(o), method_signature(n)),
+            addrs: JsonChange::make(o.method_info.addrs, n.method_info.addrs),
+        };
+
+        (diff.signature.is_some() || diff.addrs.is_some()).then_some(diff)
+    };
+
+    let mut diff = diff_members(
+        old.iter().map(|m| (method_key(m), m)),
+        new.iter().map(|m| (method_key(m), m)),
+        diff_method,
+    );
+
+    // a method without overloads whose parameters changed
+    // is a signature change rather than a removal and addition
+    let overloads = |methods: &[JsonMethod]| methods.iter().counts_by(|m| m.name.clone());
+    let old_overloads: HashMap<String, usize> = overloads(old);
+    let new_overloads: HashMap<String, usize> = overloads(new);
+
+    let signature_changes = diff
+        .removed
+        .iter()
+        .filter_map(|removed_key| {
+            let o = old.iter().find(|m| method_key(m) == *removed_key)?;
+            if old_overloads.get(&o.name) != Some(&1) || new_overloads.get(&o.name) != Some(&1) {
+                return None;
+            }
+            let n = new.iter().find(|m| m.name == o.name)?;
+            let added_key = method_key(n);
+            if !diff.added.contains(&added_key) {
+                return None;
+            }
+
+            Some((
+                removed_key.clone(),
+                added_key.clone(),
+                diff_method(&added_key, o, n)?,
+            ))
+        })
+        .collect_vec();
+
+    for (removed_key, added_key, method_diff) in signature_changes {
+        diff.removed.retain(|k| *k != removed_key);
+        diff.added.retain(|k| *k != added_key);
+        diff.changed.push(method_diff);
+    }
+    diff.changed.sort_by(|a, b| a.name.cmp(&b.name));
+
+    diff
+}
+
+fn diff_fields(old: &[JsonField], new: &[JsonField]) -> JsonMembersDiff<JsonFieldDiff> {
+    diff_members(
+        old.iter().map(|f| (f.name.clone(), f)),
+        new.iter().map(|f| (f.name.clone(), f)),
+        |name, o, n| {
+            let diff = JsonFieldDiff {
+                name: name.to_string(),
+                ty: JsonChange::make(o.ty_name.clone(), n.ty_name.clone()),
+                offset: JsonChange::make(o.offset, n.offset),
+                size: JsonChange::make(o.size, n.size),
+            };
+
+            (diff.ty.is_some() || diff.offset.is_some() || diff.size.is_some()).then_some(diff)
+        },
+    )
+}
+
+fn diff_properties(
+    old: &[JsonProperty],
+    new: &[JsonProperty],
+) -> JsonMembersDiff<JsonPropertyDiff> {
+    diff_members(
+        old.iter().map(|p| (p.name.clone(), p)),
+        new.iter().map(|p| (p.name.clone(), p)),
+        |name, o, n| {
+            Some(JsonPropertyDiff {
+                name: name.to_string(),
+                ty: JsonChange::make(o.ty_name.clone(), n.ty_name.clone())?,
+            })
+        },
+    )
+}
+
+pub fn diff_types(old: &[JsonType], new: &[JsonType]) -> JsonApiDiff {
+    let old = flatten_types(old);
+    let new = flatten_types(new);
+
+    JsonApiDiff {
+        added_types: new
+            .keys()
+            .filter(|k| !old.contains_key(*k))
+            .map(|k| k.to_string())
+            .collect(),
+        removed_types: old
+            .keys()
+            .filter(|k| !new.contains_key(*k))
+            .map(|k| k.to_string())
+            .collect(),
+        changed_types: new
+            .iter()
+            .filter_map(|(name, n)| {
+                let o = old.get(name)?;
+
+                let diff = JsonTypeDiff {
+                    full_name: name.to_string(),
+                    size: JsonChange::make(o.size, n.size),
+                    fields: diff_fields(&o.fields, &n.fields),
+                    methods: diff_methods(&o.methods, &n.methods),
+                    properties: diff_properties(&o.properties, &n.properties),
+                };
+
+                (!diff.is_empty()).then_some(diff)
+            })
+            .collect(),
+    }
+}
+
+fn fmt_offset(offset: &Option<u32>) -> String {
+    match offset {
+        Some(offset) => format!("0x{offset:x}"),
+        None => "static".to_string(),
+    }
+}
+
+fn fmt_addrs(addrs: &Option<u64>) -> String {
+    match addrs {
+        Some(addrs) => format!("0x{addrs:x}"),
+        None => "none".to_string(),
+    }
+}
+
+impl<T: Display> Display for JsonMembersDiff<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for added in &self.added {
+            writeln!(f, "    + {added}")?;
+        }
+        for removed in &self.removed {
+            writeln!(f, "    - {removed}")?;
+        }
+        for changed in &self.changed {
+            writeln!(f, "    ~ {changed}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for JsonFieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = vec![];
+        if let Some(ty) = &self.ty {
+            changes.push(format!("type {} -> {}", ty.old, ty.new));
+        }
+        if let Some(offset) = &self.offset {
+            changes.push(format!(
+                "offset {} -> {}",
+                fmt_offset(&offset.old),
+                fmt_offset(&offset.new)
+            ));
+        }
+        if let Some(size) = &self.size {
+            changes.push(format!("size 0x{:x} -> 0x{:x}", size.old, size.new));
+        }
+
+        write!(f, "field {}: {}", self.name, changes.join(", "))
+    }
+}
+
+impl Display for JsonMethodDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = vec![];
+        if let Some(signature) = &self.signature {
+            changes.push(format!("signature {} -> {}", signature.old, signature.new));
+        }
+        if let Some(addrs) = &self.addrs {
+            changes.push(format!(
+                "addr {} -> {}",
+                fmt_addrs(&addrs.old),
+                fmt_addrs(&addrs.new)
+            ));
+        }
+
+        write!(f, "method {}: {}", self.name, changes.join(", "))
+    }
+}
+
+impl Display for JsonPropertyDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "property {}: type {} -> {}",
+            self.name, self.ty.old, self.ty.new
+        )
+    }
+}
+
+impl Display for JsonApiDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for added in &self.added_types {
+            writeln!(f, "+ {added}")?;
+        }
+        for removed in &self.removed_types {
+            writeln!(f, "- {removed}")?;
+        }
+        for changed in &self.changed_types {
+            writeln!(f, "~ {}", changed.full_name)?;
+            if let Some(size) = &changed.size {
+                writeln!(f, "    size 0x{:x} -> 0x{:x}", size.old, size.new)?;
+            }
+            write!(f, "{}", changed.fields)?;
+            write!(f, "{}", changed.properties)?;
+            write!(f, "{}", changed.methods)?;
+        }
+
+        writeln!(
+            f,
+            "{} added, {} removed, {} changed types",
+            self.added_types.len(),
+            self.removed_types.len(),
+            self.changed_types.len()
+        )
+    }
+}
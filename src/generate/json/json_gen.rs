@@ -1,57 +1,94 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
+use brocolib::global_metadata::MethodIndex;
 use itertools::Itertools;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::generate::{
     cs_context_collection::TypeContextCollection,
-    cs_members::{CsField, CsMethod, CsParam, CsParamFlags, CsProperty, CsGenericTemplateType, CsGenericTemplate},
+    cs_members::{
+        CSMethodFlags, CsAttributeNamedArgKind, CsAttributeValue, CsConstructor, CsCustomAttribute,
+        CsEvent, CsField, CsGenericTemplate, CsGenericTemplateType, CsMethod, CsParam,
+        CsParamFlags, CsProperty, CsValue,
+    },
     cs_type::CsType,
     metadata::CordlMetadata,
     type_extensions::TypeDefinitionExtensions,
 };
+use crate::helpers::hash::stable_hash;
 
 use super::{
-    json_data::{JsonResolvedTypeData, JsonTypeTag},
+    json_data::{JsonResolvedTypeData, JsonTypeEnum, JsonTypeTag},
     json_name_resolver::JsonNameResolver,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Bumped whenever the json types change in a way existing readers can't handle
+pub const JSON_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonTable {
+    pub format_version: u32,
     /// associated with types by their index in types_table
-    pub types: HashMap<usize, JsonType>,
+    /// indices follow the order of the full names so they only change when types are added or removed
+    pub types: BTreeMap<usize, JsonType>,
     pub types_table: Vec<JsonTypeTag>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Written as `format.json` next to the type files of the multi json target
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonFormatInfo {
+    pub format_version: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum JsonFieldRef {
     In,
     Out,
     Ref,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonType {
+    /// Hash of the image and full name, members hash this with their signature.
+    /// Stays the same between game versions unlike `tag`
+    pub id: String,
     pub full_name: String,
     pub name: String,
     pub namespace: String,
     pub value_type: bool,
+    pub is_interface: bool,
+    pub is_enum_type: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enum_backing_type: Option<JsonTypeEnum>,
     pub fields: Vec<JsonField>,
     pub properties: Vec<JsonProperty>,
+    pub events: Vec<JsonEvent>,
     pub methods: Vec<JsonMethod>,
+    pub constructors: Vec<JsonConstructor>,
+    /// Generic arguments of the generic methods instantiated on this type
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub method_generic_instantiations: Vec<JsonMethodGenericInst>,
     pub children: Vec<JsonType>,
     pub tag: JsonTypeTag,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declaring_tag: Option<JsonTypeTag>,
+    pub nested_tags: Vec<JsonTypeTag>,
     pub parent: Option<JsonResolvedTypeData>,
+    pub interfaces: Vec<JsonResolvedTypeData>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub template: Option<JsonTemplate>,
 
     pub size: u32,
     pub packing: Option<u8>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributes: Vec<JsonCustomAttribute>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonField {
+    pub id: String,
     pub name: String,
     pub ty_name: String,
     pub ty_tag: JsonResolvedTypeData,
@@ -59,9 +96,13 @@ pub struct JsonField {
     pub is_const: bool,
     pub readonly: bool,
     pub offset: Option<u32>,
+    pub size: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributes: Vec<JsonCustomAttribute>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonProperty {
+    pub id: String,
     pub name: String,
     pub ty_name: String,
     pub ty_tag: JsonResolvedTypeData,
@@ -71,9 +112,26 @@ pub struct JsonProperty {
     pub getter: Option<(u32, String)>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub setter: Option<(u32, String)>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributes: Vec<JsonCustomAttribute>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonEvent {
+    pub id: String,
+    pub name: String,
+    pub ty_name: String,
+    pub ty_tag: JsonResolvedTypeData,
+    pub instance: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub add: Option<(u32, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remove: Option<(u32, String)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raise: Option<(u32, String)>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum JsonGenericArgumentType {
     AnyType,
     ReferenceType
@@ -81,26 +139,57 @@ pub enum JsonGenericArgumentType {
 
 type JsonTemplate = Vec<(JsonGenericArgumentType, String)>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonMethod {
+    pub id: String,
     pub name: String,
+    pub method_index: u32,
+    pub declaring_tag: JsonTypeTag,
     pub ret: String,
     pub ret_ty_tag: JsonResolvedTypeData,
     pub parameters: Vec<JsonParam>,
     pub instance: bool,
+    pub flags: JsonMethodFlags,
     pub method_info: JsonMethodInfo,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub template: Option<JsonTemplate>
+    pub template: Option<JsonTemplate>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributes: Vec<JsonCustomAttribute>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct JsonMethodFlags {
+    pub is_static: bool,
+    pub is_virtual: bool,
+    pub is_abstract: bool,
+    pub is_override: bool,
+    pub is_final: bool,
+    pub is_special_name: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonConstructor {
+    pub name: String,
+    pub parameters: Vec<JsonParam>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<JsonTemplate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonMethodGenericInst {
+    pub method_index: u32,
+    pub args: Vec<JsonResolvedTypeData>,
+    pub arg_names: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonMethodInfo {
     pub estimated_size: Option<usize>,
     pub addrs: Option<u64>,
     pub slot: Option<u16>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct JsonParam {
     pub name: String,
     pub ty: String,
@@ -108,24 +197,142 @@ pub struct JsonParam {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ref_mode: Option<JsonFieldRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub def_value: Option<JsonAttributeValue>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributes: Vec<JsonCustomAttribute>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum JsonAttributeValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Char(String),
+    String(String),
+    /// `typeof(T)`
+    Type(Option<String>),
+    Enum {
+        ty: String,
+        value: Box<JsonAttributeValue>,
+    },
+    Array(Option<Vec<JsonAttributeValue>>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub enum JsonAttributeNamedArgKind {
+    Field,
+    Property,
 }
 
-fn make_field(field: &CsField, name_resolver: &JsonNameResolver) -> JsonField {
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonAttributeNamedArg {
+    pub name: String,
+    pub kind: JsonAttributeNamedArgKind,
+    pub value: JsonAttributeValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonCustomAttribute {
+    pub name: String,
+    pub tag: JsonTypeTag,
+    pub ctor: u32,
+    pub args: Vec<JsonAttributeValue>,
+    pub named_args: Vec<JsonAttributeNamedArg>,
+}
+
+fn make_attribute_value(value: &CsAttributeValue) -> JsonAttributeValue {
+    match value {
+        CsAttributeValue::Value(v) => make_value(v),
+        CsAttributeValue::Type(t) => JsonAttributeValue::Type(t.clone()),
+        CsAttributeValue::Enum { ty, value } => JsonAttributeValue::Enum {
+            ty: ty.clone(),
+            value: Box::new(make_value(value)),
+        },
+        CsAttributeValue::Array(elements) => JsonAttributeValue::Array(
+            elements
+                .as_ref()
+                .map(|e| e.iter().map(make_attribute_value).collect_vec()),
+        ),
+    }
+}
+
+fn make_value(value: &CsValue) -> JsonAttributeValue {
+    match value {
+        CsValue::String(s) => JsonAttributeValue::String(s.clone()),
+        CsValue::Char(c) => JsonAttributeValue::Char(c.clone()),
+        CsValue::Bool(b) => JsonAttributeValue::Bool(*b),
+        CsValue::U8(x) => JsonAttributeValue::UInt(*x as u64),
+        CsValue::U16(x) => JsonAttributeValue::UInt(*x as u64),
+        CsValue::U32(x) => JsonAttributeValue::UInt(*x as u64),
+        CsValue::U64(x) => JsonAttributeValue::UInt(*x),
+        CsValue::I8(x) => JsonAttributeValue::Int(*x as i64),
+        CsValue::I16(x) => JsonAttributeValue::Int(*x as i64),
+        CsValue::I32(x) => JsonAttributeValue::Int(*x as i64),
+        CsValue::I64(x) => JsonAttributeValue::Int(*x),
+        CsValue::F32(f) => JsonAttributeValue::Float(*f as f64),
+        CsValue::F64(f) => JsonAttributeValue::Float(*f),
+        CsValue::Object(_) | CsValue::ValueType(_) | CsValue::Null => JsonAttributeValue::Null,
+    }
+}
+
+fn make_attributes(attributes: &[CsCustomAttribute]) -> Vec<JsonCustomAttribute> {
+    attributes
+        .iter()
+        .map(|a| JsonCustomAttribute {
+            name: a.name.clone(),
+            tag: a.ty.into(),
+            ctor: a.ctor.index(),
+            args: a.args.iter().map(make_attribute_value).collect_vec(),
+            named_args: a
+                .named_args
+                .iter()
+                .map(|n| JsonAttributeNamedArg {
+                    name: n.name.clone(),
+                    kind: match n.kind {
+                        CsAttributeNamedArgKind::Field => JsonAttributeNamedArgKind::Field,
+                        CsAttributeNamedArgKind::Property => JsonAttributeNamedArgKind::Property,
+                    },
+                    value: make_attribute_value(&n.value),
+                })
+                .collect_vec(),
+        })
+        .collect_vec()
+}
+
+///
+/// Hash of `parts` as hex, e.g the image and full name of a type
+/// or the type id and signature of a member
+///
+fn stable_id(parts: &[&str]) -> String {
+    format!("{:016x}", stable_hash(parts.join("\0").as_bytes()))
+}
+
+fn make_field(field: &CsField, type_id: &str, name_resolver: &JsonNameResolver) -> JsonField {
     let ty: JsonResolvedTypeData = field.field_ty.clone().into();
     let ty_name = name_resolver.resolve_name(&field.field_ty).combine_all();
     let offset = field.offset;
 
     JsonField {
+        id: stable_id(&[type_id, "field", &field.name]),
         name: field.name.to_string(),
         ty_name,
         offset,
+        size: field.size,
         ty_tag: ty,
         instance: field.instance,
         is_const: field.is_const,
-        readonly: field.readonly
+        readonly: field.readonly,
+        attributes: make_attributes(&field.attributes),
     }
 }
-fn make_property(property: &CsProperty, name_resolver: &JsonNameResolver) -> JsonProperty {
+fn make_property(
+    property: &CsProperty,
+    type_id: &str,
+    name_resolver: &JsonNameResolver,
+) -> JsonProperty {
     let p_setter = property
         .setter
         .as_ref()
@@ -139,6 +346,7 @@ fn make_property(property: &CsProperty, name_resolver: &JsonNameResolver) -> Jso
     let ty_name = name_resolver.resolve_name(&property.prop_ty).combine_all();
 
     JsonProperty {
+        id: stable_id(&[type_id, "property", &property.name]),
         name: property.name.to_string(),
         ty_tag: p_type,
         ty_name,
@@ -146,6 +354,26 @@ fn make_property(property: &CsProperty, name_resolver: &JsonNameResolver) -> Jso
         indexable: property.indexable,
         setter: p_setter,
         getter: p_getter,
+        attributes: make_attributes(&property.attributes),
+    }
+}
+fn make_event(event: &CsEvent, type_id: &str, name_resolver: &JsonNameResolver) -> JsonEvent {
+    let make_accessor = |accessor: &Option<(MethodIndex, String)>| {
+        accessor.as_ref().map(|(i, s)| (i.index(), s.to_string()))
+    };
+
+    let e_type: JsonResolvedTypeData = event.event_ty.clone().into();
+    let ty_name = name_resolver.resolve_name(&event.event_ty).combine_all();
+
+    JsonEvent {
+        id: stable_id(&[type_id, "event", &event.name]),
+        name: event.name.to_string(),
+        ty_tag: e_type,
+        ty_name,
+        instance: event.instance,
+        add: make_accessor(&event.add),
+        remove: make_accessor(&event.remove),
+        raise: make_accessor(&event.raise),
     }
 }
 fn make_param(param: &CsParam, name_resolver: &JsonNameResolver) -> JsonParam {
@@ -167,6 +395,8 @@ fn make_param(param: &CsParam, name_resolver: &JsonNameResolver) -> JsonParam {
         ty: ty_name,
         ty_tag: param_type,
         ref_mode,
+        def_value: param.def_value.as_ref().map(make_value),
+        attributes: make_attributes(&param.attributes),
     }
 }
 
@@ -181,7 +411,30 @@ fn make_template(template: &CsGenericTemplate) -> JsonTemplate {
         }, p.1.clone())).collect_vec();
 }
 
-fn make_method(method: &CsMethod, name_resolver: &JsonNameResolver) -> JsonMethod {
+fn make_method_flags(flags: &CSMethodFlags) -> JsonMethodFlags {
+    JsonMethodFlags {
+        is_static: flags.contains(CSMethodFlags::STATIC),
+        is_virtual: flags.contains(CSMethodFlags::VIRTUAL),
+        is_abstract: flags.contains(CSMethodFlags::ABSTRACT),
+        is_override: flags.contains(CSMethodFlags::OVERRIDE),
+        is_final: flags.contains(CSMethodFlags::FINAL),
+        is_special_name: flags.contains(CSMethodFlags::SPECIAL_NAME),
+    }
+}
+
+fn make_constructor(ctor: &CsConstructor, name_resolver: &JsonNameResolver) -> JsonConstructor {
+    JsonConstructor {
+        name: ctor.name.clone(),
+        parameters: ctor
+            .parameters
+            .iter()
+            .map(|p| make_param(p, name_resolver))
+            .collect_vec(),
+        template: ctor.template.as_ref().map(make_template),
+    }
+}
+
+fn make_method(method: &CsMethod, type_id: &str, name_resolver: &JsonNameResolver) -> JsonMethod {
     let ret_ty_name = name_resolver
         .resolve_name(&method.return_type)
         .combine_all();
@@ -193,6 +446,18 @@ fn make_method(method: &CsMethod, name_resolver: &JsonNameResolver) -> JsonMetho
         .map(|p| make_param(p, name_resolver))
         .collect_vec();
 
+    // overloads differ by parameters or generic arity
+    let signature = format!(
+        "{ret_ty_name} {}<{}>({})",
+        method.name,
+        method
+            .template
+            .as_ref()
+            .map(|t| t.just_names().join(","))
+            .unwrap_or_default(),
+        params.iter().map(|p| &p.ty).join(",")
+    );
+
     let json_method_info = JsonMethodInfo {
         addrs: method.method_data.addrs,
         estimated_size: method.method_data.estimated_size,
@@ -200,16 +465,21 @@ fn make_method(method: &CsMethod, name_resolver: &JsonNameResolver) -> JsonMetho
     };
 
     JsonMethod {
+        id: stable_id(&[type_id, "method", &signature]),
         name: method.name.to_string(),
+        method_index: method.method_index.index(),
+        declaring_tag: method.declaring_type.into(),
         parameters: params,
         instance: method.instance,
+        flags: make_method_flags(&method.method_flags),
         ret: ret_ty_name,
         ret_ty_tag: ret_ty,
         method_info: json_method_info,
         template: match&(method.template) {
             Some(t) => Some(make_template(t)),
             None => None
-        }
+        },
+        attributes: make_attributes(&method.attributes),
     }
 }
 
@@ -225,23 +495,53 @@ pub fn make_type(
 
     let parent: Option<JsonResolvedTypeData> = td.parent.clone().map(|p| p.into());
 
+    let full_name = td.cs_name_components.combine_all();
+    let image = metadata
+        .image_name(td.self_tag.get_tdi())
+        .unwrap_or_default();
+    let id = stable_id(&[image, &full_name]);
+
     let fields = td
         .fields
         .iter()
-        .map(|f| make_field(f, &name_resolver))
+        .map(|f| make_field(f, &id, &name_resolver))
         .collect_vec();
     let properties = td
         .properties
         .iter()
-        .map(|f| make_property(f, &name_resolver))
+        .map(|f| make_property(f, &id, &name_resolver))
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect_vec();
+    let events = td
+        .events
+        .iter()
+        .map(|e| make_event(e, &id, &name_resolver))
         .sorted_by(|a, b| a.name.cmp(&b.name))
         .collect_vec();
     let methods = td
         .methods
         .iter()
-        .map(|f| make_method(f, &name_resolver))
+        .map(|f| make_method(f, &id, &name_resolver))
         .sorted_by(|a, b| a.name.cmp(&b.name))
         .collect_vec();
+    let constructors = td
+        .constructors
+        .iter()
+        .map(|c| make_constructor(c, &name_resolver))
+        .collect_vec();
+    let method_generic_instantiations = td
+        .method_generic_instantiation_map
+        .iter()
+        .map(|(method_index, args)| JsonMethodGenericInst {
+            method_index: method_index.index(),
+            arg_names: args
+                .iter()
+                .map(|a| name_resolver.resolve_name(a).combine_all())
+                .collect_vec(),
+            args: args.iter().cloned().map(|a| a.into()).collect_vec(),
+        })
+        .sorted_by_key(|i| i.method_index)
+        .collect_vec();
 
     let children = td
         .nested_types
@@ -254,6 +554,19 @@ pub fn make_type(
         .sorted_by(|a, b| a.name.cmp(&b.name))
         .collect_vec();
 
+    let nested_tags = td
+        .nested_types
+        .iter()
+        .map(|t| JsonTypeTag::from(*t))
+        .sorted()
+        .collect_vec();
+    let interfaces = td
+        .interfaces
+        .iter()
+        .cloned()
+        .map(|i| i.into())
+        .collect_vec();
+
     let namespace = td.namespace().to_string();
     let name = td.name().to_string();
 
@@ -261,13 +574,20 @@ pub fn make_type(
     let packing = td.packing;
 
     JsonType {
-        full_name: td.cs_name_components.combine_all(),
+        id,
+        full_name,
         namespace,
         name,
         value_type: td.is_value_type,
+        is_interface: td.is_interface,
+        is_enum_type: td.is_enum_type,
+        enum_backing_type: td.enum_backing_type.map(|t| t.into()),
         fields,
         properties,
+        events,
         methods,
+        constructors,
+        method_generic_instantiations,
         children,
         template: match&(td.generic_template) {
             Some(t) => Some(make_template(t)),
@@ -276,6 +596,10 @@ pub fn make_type(
         packing,
         size,
         tag: td.self_tag.into(),
+        declaring_tag: td.declaring_ty.map(|t| t.into()),
+        nested_tags,
         parent,
+        interfaces,
+        attributes: make_attributes(&td.attributes),
     }
 }
@@ -0,0 +1,361 @@
+use std::{fs, path::Path};
+
+use color_eyre::eyre::{ensure, eyre, Context};
+use itertools::Itertools;
+use jsonschema::Validator;
+use log::info;
+use schemars::{schema::RootSchema, schema_for};
+use walkdir::WalkDir;
+
+use super::json_gen::{JsonFormatInfo, JsonTable, JsonType, JSON_FORMAT_VERSION};
+
+type Result<T> = std::result::Result<T, color_eyre::eyre::Report>;
+
+/// Holds the format version of a multi json folder
+pub const FORMAT_FILE: &str = "format.json";
+
+/// Schema of the single json target
+pub const TABLE_SCHEMA_FILE: &str = "cordl.schema.json";
+/// Schema of each type file of the multi json target
+pub const TYPE_SCHEMA_FILE: &str = "cordl_type.schema.json";
+/// Schema of the `format.json` file of the multi json target
+pub const FORMAT_SCHEMA_FILE: &str = "cordl_format.schema.json";
+
+fn write_schema(mut schema: RootSchema, file: &Path) -> Result<()> {
+    let metadata = schema.schema.metadata();
+    metadata.description = Some(format!("cordl json format version {JSON_FORMAT_VERSION}"));
+
+    fs::write(file, serde_json::to_vec_pretty(&schema)?)
+        .with_context(|| format!("Failed to write {file:?}"))
+}
+
+///
+/// Writes the JSON Schemas of the single and multi json targets to `folder`
+///
+pub fn write_schemas(folder: &Path) -> Result<()> {
+    fs::create_dir_all(folder)?;
+
+    write_schema(schema_for!(JsonTable), &folder.join(TABLE_SCHEMA_FILE))?;
+    write_schema(schema_for!(JsonType), &folder.join(TYPE_SCHEMA_FILE))?;
+    write_schema(
+        schema_for!(JsonFormatInfo),
+        &folder.join(FORMAT_SCHEMA_FILE),
+    )?;
+
+    info!("Wrote json format version {JSON_FORMAT_VERSION} schemas to {folder:?}");
+    Ok(())
+}
+
+fn check_version(format_version: u32, path: &Path) -> Result<()> {
+    ensure!(
+        format_version == JSON_FORMAT_VERSION,
+        "{path:?} has format version {format_version}, this build reads version {JSON_FORMAT_VERSION}"
+    );
+    Ok(())
+}
+
+fn read_versioned(path: &Path) -> Result<Vec<u8>> {
+    let data = fs::read(path).with_context(|| format!("Failed to read {path:?}"))?;
+    let format_info: JsonFormatInfo =
+        serde_json::from_slice(&data).with_context(|| format!("{path:?} has no format_version"))?;
+    check_version(format_info.format_version, path)?;
+
+    Ok(data)
+}
+
+fn check_types_table(table: &JsonTable, path: &Path) -> Result<()> {
+    ensure!(
+        table.types.len() == table.types_table.len(),
+        "{path:?} has {} types but {} entries in types_table",
+        table.types.len(),
+        table.types_table.len()
+    );
+    Ok(())
+}
+
+/// Compiles a schema as written by `write_schemas`
+fn make_validator(schema: RootSchema) -> Result<Validator> {
+    let schema = serde_json::to_value(schema)?;
+    jsonschema::validator_for(&schema).map_err(|e| eyre!("Invalid json schema: {e}"))
+}
+
+/// Checks `data` against the schema, listing every mismatch by its path in the document
+fn validate_data(validator: &Validator, data: &[u8], path: &Path) -> Result<serde_json::Value> {
+    let instance: serde_json::Value =
+        serde_json::from_slice(data).with_context(|| format!("{path:?} is not valid json"))?;
+
+    let errors = validator
+        .iter_errors(&instance)
+        .map(|e| format!("  {}: {e}", e.instance_path))
+        .collect_vec();
+    ensure!(
+        errors.is_empty(),
+        "{path:?} does not match the schema:\n{}",
+        errors.join("\n")
+    );
+
+    Ok(instance)
+}
+
+///
+/// Checks that a single json file or multi json folder matches the current format,
+/// validating against the schemas written by `write_schemas`
+///
+pub fn validate_json(path: &Path) -> Result<()> {
+    if !path.is_dir() {
+        let data = read_versioned(path)?;
+        let instance = validate_data(&make_validator(schema_for!(JsonTable))?, &data, path)?;
+
+        let table: JsonTable = serde_json::from_value(instance)
+            .with_context(|| format!("{path:?} does not match the json format"))?;
+        check_types_table(&table, path)?;
+
+        info!("{path:?} is valid, {} types", table.types.len());
+        return Ok(());
+    }
+
+    let format_path = path.join(FORMAT_FILE);
+    let data = read_versioned(&format_path)?;
+    validate_data(
+        &make_validator(schema_for!(JsonFormatInfo))?,
+        &data,
+        &format_path,
+    )?;
+
+    let type_validator = make_validator(schema_for!(JsonType))?;
+    let mut count = 0;
+    for entry in WalkDir::new(path).min_depth(2) {
+        let entry = entry?;
+        if !entry.file_type().is_file() || entry.path().extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+
+        let data = fs::read(entry.path())?;
+        validate_data(&type_validator, &data, entry.path())?;
+        count += 1;
+    }
+
+    info!("{path:?} is valid, {count} types");
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::generate::json::{
+        json_data::{JsonResolvedTypeData, JsonTypeEnum, JsonTypeTag},
+        json_gen::{
+            JsonAttributeNamedArg, JsonAttributeNamedArgKind, JsonAttributeValue, JsonConstructor,
+            JsonCustomAttribute, JsonEvent, JsonField, JsonFieldRef, JsonGenericArgumentType,
+            JsonMethod, JsonMethodFlags, JsonMethodGenericInst, JsonMethodInfo, JsonParam,
+            JsonProperty,
+        },
+    };
+
+    fn attribute() -> JsonCustomAttribute {
+        JsonCustomAttribute {
+            name: "System.ObsoleteAttribute".to_string(),
+            tag: JsonTypeTag::TypeDefinition(7),
+            ctor: 12,
+            args: vec![
+                JsonAttributeValue::String("Use Bar".to_string()),
+                JsonAttributeValue::Float(0.5),
+                JsonAttributeValue::Array(Some(vec![JsonAttributeValue::Int(-1)])),
+                JsonAttributeValue::Enum {
+                    ty: "Foo.Mode".to_string(),
+                    value: Box::new(JsonAttributeValue::UInt(2)),
+                },
+            ],
+            named_args: vec![JsonAttributeNamedArg {
+                name: "IsError".to_string(),
+                kind: JsonAttributeNamedArgKind::Property,
+                value: JsonAttributeValue::Bool(true),
+            }],
+        }
+    }
+
+    pub(crate) fn json_type() -> JsonType {
+        let tag = JsonTypeTag::GenericInstantiation {
+            type_definition: 3,
+            inst: 1,
+        };
+        let int = JsonResolvedTypeData::Primitive(JsonTypeEnum::I4);
+        let list = JsonResolvedTypeData::GenericInst(
+            Box::new(JsonResolvedTypeData::Type(JsonTypeTag::TypeDefinition(4))),
+            vec![(JsonResolvedTypeData::GenericArg(3, 0), false)],
+        );
+        let template = vec![(JsonGenericArgumentType::AnyType, "T".to_string())];
+
+        JsonType {
+            id: "0011223344556677".to_string(),
+            full_name: "Foo.Bar`1".to_string(),
+            name: "Bar`1".to_string(),
+            namespace: "Foo".to_string(),
+            value_type: false,
+            is_interface: false,
+            is_enum_type: false,
+            enum_backing_type: None,
+            fields: vec![JsonField {
+                id: "1".to_string(),
+                name: "_items".to_string(),
+                ty_name: "System.Collections.Generic.List`1<T>".to_string(),
+                ty_tag: list.clone(),
+                instance: true,
+                is_const: false,
+                readonly: true,
+                offset: Some(0x10),
+                size: 8,
+                attributes: vec![attribute()],
+            }],
+            properties: vec![JsonProperty {
+                id: "2".to_string(),
+                name: "Count".to_string(),
+                ty_name: "int".to_string(),
+                ty_tag: int.clone(),
+                instance: true,
+                indexable: false,
+                getter: Some((20, "get_Count".to_string())),
+                setter: None,
+                attributes: vec![],
+            }],
+            events: vec![JsonEvent {
+                id: "3".to_string(),
+                name: "Changed".to_string(),
+                ty_name: "System.Action".to_string(),
+                ty_tag: JsonResolvedTypeData::Type(JsonTypeTag::TypeDefinition(5)),
+                instance: false,
+                add: Some((21, "add_Changed".to_string())),
+                remove: Some((22, "remove_Changed".to_string())),
+                raise: None,
+            }],
+            methods: vec![JsonMethod {
+                id: "4".to_string(),
+                name: "Get".to_string(),
+                method_index: 23,
+                declaring_tag: tag.clone(),
+                ret: "T".to_string(),
+                ret_ty_tag: JsonResolvedTypeData::GenericArg(3, 0),
+                parameters: vec![JsonParam {
+                    name: "index".to_string(),
+                    ty: "int".to_string(),
+                    ty_tag: JsonResolvedTypeData::ByRef(Box::new(int.clone())),
+                    ref_mode: Some(JsonFieldRef::Ref),
+                    def_value: Some(JsonAttributeValue::Int(0)),
+                    attributes: vec![],
+                }],
+                instance: true,
+                flags: JsonMethodFlags {
+                    is_virtual: true,
+                    ..Default::default()
+                },
+                method_info: JsonMethodInfo {
+                    estimated_size: Some(64),
+                    addrs: Some(0x1234_5678),
+                    slot: Some(4),
+                },
+                template: Some(template.clone()),
+                attributes: vec![attribute()],
+            }],
+            constructors: vec![JsonConstructor {
+                name: "New".to_string(),
+                parameters: vec![],
+                template: None,
+            }],
+            method_generic_instantiations: vec![JsonMethodGenericInst {
+                method_index: 23,
+                args: vec![JsonResolvedTypeData::Array(Box::new(int))],
+                arg_names: vec!["int".to_string()],
+            }],
+            children: vec![],
+            tag,
+            declaring_tag: None,
+            nested_tags: vec![],
+            parent: Some(JsonResolvedTypeData::Type(JsonTypeTag::TypeDefinition(1))),
+            interfaces: vec![list],
+            template: Some(template),
+            size: 0x18,
+            packing: None,
+            attributes: vec![attribute()],
+        }
+    }
+
+    fn json_table() -> JsonTable {
+        let ty = json_type();
+        JsonTable {
+            format_version: JSON_FORMAT_VERSION,
+            types_table: vec![ty.tag.clone()],
+            types: BTreeMap::from([(0, ty)]),
+        }
+    }
+
+    fn validate<T: serde::Serialize>(schema: RootSchema, value: &T) -> Result<serde_json::Value> {
+        let data = serde_json::to_vec(value)?;
+        validate_data(&make_validator(schema)?, &data, Path::new("test.json"))
+    }
+
+    #[test]
+    fn table_matches_schema() {
+        validate(schema_for!(JsonTable), &json_table()).unwrap();
+    }
+
+    #[test]
+    fn type_matches_schema() {
+        validate(schema_for!(JsonType), &json_type()).unwrap();
+        validate(
+            schema_for!(JsonFormatInfo),
+            &JsonFormatInfo {
+                format_version: JSON_FORMAT_VERSION,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn schema_rejects_mismatches() {
+        let mut table = serde_json::to_value(json_table()).unwrap();
+        table["types"]["0"]["fields"][0]["offset"] = "16".into();
+        table["types"]["0"]["methods"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("flags");
+
+        let error = validate(schema_for!(JsonTable), &table)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("/types/0/fields/0/offset"), "{error}");
+        assert!(error.contains("/types/0/methods/0"), "{error}");
+    }
+
+    #[test]
+    fn validates_json_files() {
+        let dir = std::env::temp_dir().join(format!("cordl_json_schema_{}", std::process::id()));
+        let types_dir = dir.join("Foo");
+        fs::create_dir_all(&types_dir).unwrap();
+
+        let table_path = dir.join("cordl.json");
+        fs::write(&table_path, serde_json::to_vec(&json_table()).unwrap()).unwrap();
+        validate_json(&table_path).unwrap();
+
+        let format_info = JsonFormatInfo {
+            format_version: JSON_FORMAT_VERSION,
+        };
+        fs::write(
+            dir.join(FORMAT_FILE),
+            serde_json::to_vec(&format_info).unwrap(),
+        )
+        .unwrap();
+        fs::write(
+            types_dir.join("Bar`1.json"),
+            serde_json::to_vec(&json_type()).unwrap(),
+        )
+        .unwrap();
+        validate_json(&dir).unwrap();
+
+        fs::write(types_dir.join("Broken.json"), br#"{"id": 1}"#).unwrap();
+        assert!(validate_json(&dir).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
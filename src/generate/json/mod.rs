@@ -1,54 +1,46 @@
 use std::{
-    collections::HashMap,
+    collections::HashSet,
     fs::{self, File},
-    io::BufWriter,
+    io::{BufWriter, Write},
     path::{Path, PathBuf},
 };
 
 use itertools::Itertools;
 use json_data::JsonTypeTag;
-use json_gen::{make_type, JsonTable, JsonType};
+use json_gen::{make_type, JsonFormatInfo, JsonTable, JsonType, JSON_FORMAT_VERSION};
 
 use super::{
     cs_context_collection::TypeContextCollection,
     cs_type::CsType,
+    cs_type_tag::CsTypeTag,
     metadata::CordlMetadata,
     type_extensions::{TypeDefinitionExtensions, TypeDefinitionIndexExtensions},
 };
 
 mod json_data;
+mod json_diff;
 mod json_gen;
 mod json_name_resolver;
+mod json_schema;
+
+pub use json_schema::{validate_json, write_schemas};
 
 type Result<T> = std::result::Result<T, color_eyre::eyre::Report>;
 
 pub fn make_json(
     metadata: &CordlMetadata,
     collection: &TypeContextCollection,
+    only: Option<&HashSet<CsTypeTag>>,
     file: &Path,
     format: bool,
 ) -> Result<()> {
-    // we could use a map here but sorting
-    // wouldn't be guaranteed
-    // we want sorting so diffs are more readable
-    let json_objects: HashMap<usize, JsonType> = collection
-        .get()
-        .values()
-        .flat_map(|c| c.get_types().values())
-        // skip compiler generated types
-        .filter(|t| is_real_declaring_type(t, metadata))
-        .enumerate()
-        .map(|(i, td)| (i, make_type(td, metadata, collection)))
-        .sorted_by(|a, b| a.1.full_name.cmp(&b.1.full_name))
-        .collect();
+    // indices are assigned after sorting so they are the same between runs
+    let json_objects = make_json_types(metadata, collection, only);
 
     let table = JsonTable {
-        types_table: json_objects
-            .iter()
-            .sorted_by(|a, b| a.0.cmp(b.0))
-            .map(|t| t.1.tag.clone())
-            .collect(),
-        types: json_objects,
+        format_version: JSON_FORMAT_VERSION,
+        types_table: json_objects.iter().map(|t| t.tag.clone()).collect(),
+        types: json_objects.into_iter().enumerate().collect(),
     };
 
     let file = File::create(file)?;
@@ -65,19 +57,20 @@ pub fn make_json(
 pub fn make_json_folder(
     metadata: &CordlMetadata,
     collection: &TypeContextCollection,
+    only: Option<&HashSet<CsTypeTag>>,
     folder: &Path,
 ) -> Result<()> {
-    // we could use a map here but sorting
-    // wouldn't be guaranteed
-    // we want sorting so diffs are more readable
-    collection
-        .get()
-        .values()
-        .flat_map(|c| c.get_types().values())
-        // skip compiler generated types
-        .filter(|t| is_real_declaring_type(t, metadata))
-        .map(|td| make_type(td, metadata, collection))
-        .sorted_by(|a, b| a.full_name.cmp(&b.full_name))
+    fs::create_dir_all(folder)?;
+    let format_info = JsonFormatInfo {
+        format_version: JSON_FORMAT_VERSION,
+    };
+    fs::write(
+        folder.join(json_schema::FORMAT_FILE),
+        serde_json::to_vec_pretty(&format_info)?,
+    )?;
+
+    make_json_types(metadata, collection, only)
+        .into_iter()
         .try_for_each(|t| -> Result<()> {
             let mut namespace = t.namespace.clone();
             let name = t.name.clone();
@@ -101,6 +94,90 @@ pub fn make_json_folder(
     Ok(())
 }
 
+///
+/// Makes the types written to json and compared by `cordl diff`,
+/// sorted by full name then id so the order is the same between runs
+///
+pub fn make_json_types(
+    metadata: &CordlMetadata,
+    collection: &TypeContextCollection,
+    only: Option<&HashSet<CsTypeTag>>,
+) -> Vec<JsonType> {
+    collection
+        .get()
+        .iter()
+        .filter(|(tag, _)| only.is_none_or(|only| only.contains(tag)))
+        .flat_map(|(_, c)| c.get_types().values())
+        // skip compiler generated types
+        .filter(|t| is_real_declaring_type(t, metadata))
+        .map(|td| make_type(td, metadata, collection))
+        .sorted_by(|a, b| a.full_name.cmp(&b.full_name).then_with(|| a.id.cmp(&b.id)))
+        .collect()
+}
+
+///
+/// The types of `make_json_types` as json, for `cordl diff` between metadata versions.
+/// Each version is read by its own build of cordl, so the types are passed between them serialized
+///
+pub fn make_json_types_data(
+    metadata: &CordlMetadata,
+    collection: &TypeContextCollection,
+) -> Result<Vec<u8>> {
+    let types = make_json_types(metadata, collection, None);
+
+    Ok(serde_json::to_vec(&types)?)
+}
+
+///
+/// `write_api_diff` for types serialized by `make_json_types_data`, possibly by another build
+///
+pub fn write_api_diff_data(
+    old: &[u8],
+    new: &[u8],
+    text_file: Option<&Path>,
+    json_file: Option<&Path>,
+    format: bool,
+) -> Result<()> {
+    let old: Vec<JsonType> = serde_json::from_slice(old)?;
+    let new: Vec<JsonType> = serde_json::from_slice(new)?;
+
+    write_api_diff(&old, &new, text_file, json_file, format)
+}
+
+///
+/// Writes the differences between two versions as text, to `text_file` or stdout,
+/// and optionally as json
+///
+pub fn write_api_diff(
+    old: &[JsonType],
+    new: &[JsonType],
+    text_file: Option<&Path>,
+    json_file: Option<&Path>,
+    format: bool,
+) -> Result<()> {
+    let diff = json_diff::diff_types(old, new);
+
+    match text_file {
+        Some(text_file) => {
+            let mut file = File::create(text_file)?;
+            write!(file, "{diff}")?;
+        }
+        None => print!("{diff}"),
+    }
+
+    if let Some(json_file) = json_file {
+        let file = File::create(json_file)?;
+        let mut buf_writer = BufWriter::new(file);
+
+        match format {
+            true => serde_json::to_writer_pretty(&mut buf_writer, &diff)?,
+            false => serde_json::to_writer(&mut buf_writer, &diff)?,
+        };
+    }
+
+    Ok(())
+}
+
 ///
 /// Essentially check if the type is compiler generated or
 /// not useful to emit
@@ -132,3 +209,40 @@ pub fn is_real_declaring_type(ty: &CsType, metadata: &CordlMetadata) -> bool {
         && condition3
         && condition4
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{json_diff::JsonApiDiff, json_schema::tests::json_type, write_api_diff_data};
+
+    #[test]
+    fn diffs_serialized_types() {
+        let old = vec![json_type()];
+        let mut new = old.clone();
+        new[0].size += 8;
+        new[0].fields.clear();
+
+        let dir = std::env::temp_dir().join(format!("cordl_json_diff_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let json_file = dir.join("diff.json");
+        write_api_diff_data(
+            &serde_json::to_vec(&old).unwrap(),
+            &serde_json::to_vec(&new).unwrap(),
+            Some(&dir.join("diff.txt")),
+            Some(&json_file),
+            false,
+        )
+        .unwrap();
+
+        let diff: JsonApiDiff = serde_json::from_slice(&fs::read(&json_file).unwrap()).unwrap();
+        let [changed] = diff.changed_types.as_slice() else {
+            panic!("expected one changed type, got {diff:?}");
+        };
+        assert_eq!(changed.full_name, old[0].full_name);
+        assert!(changed.size.as_ref().is_some_and(|s| s.old + 8 == s.new));
+        assert_eq!(changed.fields.removed.len(), old[0].fields.len());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,22 +1,87 @@
-use std::collections::{HashMap, HashSet};
-
-use brocolib::global_metadata::{Il2CppTypeDefinition, MethodIndex, TypeDefinitionIndex};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+};
+
+use brocolib::global_metadata::{
+    FieldIndex, Il2CppTypeDefinition, MethodIndex, ParameterIndex, TypeDefinitionIndex,
+};
 use itertools::Itertools;
 
+use crate::data::profile::BlacklistPatternKind;
+
 use super::cs_type::CsType;
 
+/// The il2cpp metadata versions cordl can read, each read by its own build of brocolib
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataVersion {
+    V29,
+    V31,
+}
+
+impl MetadataVersion {
+    pub const ALL: [MetadataVersion; 2] = [MetadataVersion::V29, MetadataVersion::V31];
+
+    pub fn from_number(version: u32) -> Option<MetadataVersion> {
+        Self::ALL.into_iter().find(|v| v.number() == version)
+    }
+
+    pub fn number(self) -> u32 {
+        match self {
+            MetadataVersion::V29 => 29,
+            MetadataVersion::V31 => 31,
+        }
+    }
+}
+
+/// The metadata version the brocolib of this copy of cordl reads
+#[cfg(feature = "il2cpp_v29")]
+pub const SUPPORTED_METADATA_VERSION: MetadataVersion = MetadataVersion::V29;
+#[cfg(feature = "il2cpp_v31")]
+pub const SUPPORTED_METADATA_VERSION: MetadataVersion = MetadataVersion::V31;
+
+const METADATA_SANITY: u32 = 0xFAB11BAF;
+
+///
+/// Reads the version from the global-metadata.dat header
+/// `None` if the sanity value does not match, e.g for encrypted metadata
+///
+pub fn read_metadata_version(global_metadata_data: &[u8]) -> Option<u32> {
+    let header = global_metadata_data.get(0..8)?;
+    let sanity = u32::from_le_bytes(header[0..4].try_into().unwrap());
+    let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+
+    (sanity == METADATA_SANITY).then_some(version)
+}
+
 pub struct MethodCalculations {
     pub estimated_size: usize,
     pub addrs: u64,
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum PointerSize {
     Bytes4 = 4,
     Bytes8 = 8,
 }
 
+impl PointerSize {
+    /// Reads the class (32 or 64 bit) from the ELF header
+    /// `None` if the binary is not an ELF
+    pub fn from_elf(elf_data: &[u8]) -> Option<PointerSize> {
+        if !elf_data.starts_with(b"\x7fELF") {
+            return None;
+        }
+
+        match elf_data.get(4)? {
+            1 => Some(PointerSize::Bytes4),
+            2 => Some(PointerSize::Bytes8),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct TypeDefinitionPair<'a> {
     pub ty: &'a Il2CppTypeDefinition,
@@ -52,7 +117,21 @@ pub struct CordlMetadata<'a> {
     pub object_tdi: TypeDefinitionIndex,
 
     pub name_to_tdi: HashMap<Il2cppFullName<'a>, TypeDefinitionIndex>,
+    /// `Il2CppTypeDefinition::full_name` without generics -> TDI, nested types included
+    /// e.g `UnityEngine.InputSystem.Utilities.InputActionTrace::Enumerator`
+    pub full_name_to_tdi: HashMap<String, TypeDefinitionIndex>,
+    /// Field -> index into `field_default_values`
+    pub field_default_value_indices: HashMap<FieldIndex, usize>,
+    /// Parameter -> index into `parameter_default_values`
+    pub parameter_default_value_indices: HashMap<ParameterIndex, usize>,
+    /// Range into `attribute_data_ranges` of the image declaring the type
+    /// Tokens are only unique per image, so lookups must stay within this range
+    pub custom_attribute_ranges: HashMap<TypeDefinitionIndex, Range<usize>>,
     pub blacklisted_types: HashSet<TypeDefinitionIndex>,
+    /// Types blacklisted because they failed to generate, with the error
+    pub failed_types: HashMap<TypeDefinitionIndex, String>,
+    /// Profile blacklist patterns which matched no type
+    pub unmatched_blacklist_patterns: Vec<(BlacklistPatternKind, String)>,
 
     pub pointer_size: PointerSize,
     pub packing_field_offset: u8,
@@ -68,11 +147,80 @@ impl<'a> CordlMetadata<'a> {
         (self.pointer_size as u8) * 2
     }
 
+    /// Name of the image declaring the type e.g `UnityEngine.CoreModule.dll`
+    pub fn image_name(&self, tdi: TypeDefinitionIndex) -> Option<&'a str> {
+        self.metadata
+            .global_metadata
+            .images
+            .as_vec()
+            .iter()
+            .find(|img| {
+                let type_start = img.type_start.index();
+                (type_start..type_start + img.type_count).contains(&tdi.index())
+            })
+            .map(|img| img.name(self.metadata))
+    }
+
     pub fn parse(&mut self) {
         let gm = &self.metadata.global_metadata;
         self.parse_name_tdi(gm);
+        self.parse_full_name_tdi(gm);
+        self.parse_core_tdis();
+        self.parse_default_values(gm);
         self.parse_type_hierarchy(gm);
         self.parse_method_size(gm);
+        self.parse_custom_attribute_ranges(gm);
+    }
+
+    /// The type with this full name, see `full_name_to_tdi`
+    pub fn get_tdi(&self, full_name: &str) -> Option<TypeDefinitionIndex> {
+        self.full_name_to_tdi.get(full_name).copied()
+    }
+
+    ///
+    /// Maps the full name of every type to its TDI
+    /// The first definition wins if several assemblies declare the same name
+    ///
+    fn parse_full_name_tdi(&mut self, gm: &brocolib::global_metadata::GlobalMetadata) {
+        self.full_name_to_tdi.clear();
+        for (tdi, td) in gm.type_definitions.as_vec().iter().enumerate() {
+            self.full_name_to_tdi
+                .entry(td.full_name(self.metadata, false))
+                .or_insert(TypeDefinitionIndex::new(tdi as u32));
+        }
+    }
+
+    fn parse_core_tdis(&mut self) {
+        let get_tdi = |full_name: &str| {
+            self.get_tdi(full_name)
+                .unwrap_or_else(|| panic!("Unable to find TDI for {full_name}"))
+        };
+
+        let unity_object_tdi = get_tdi("UnityEngine.Object");
+        let object_tdi = get_tdi("System.Object");
+        let string_tdi = get_tdi("System.String");
+
+        self.unity_object_tdi = unity_object_tdi;
+        self.object_tdi = object_tdi;
+        self.string_tdi = string_tdi;
+    }
+
+    fn parse_default_values(&mut self, gm: &brocolib::global_metadata::GlobalMetadata) {
+        self.field_default_value_indices = gm
+            .field_default_values
+            .as_vec()
+            .iter()
+            .enumerate()
+            .map(|(i, def)| (def.field_index, i))
+            .collect();
+
+        self.parameter_default_value_indices = gm
+            .parameter_default_values
+            .as_vec()
+            .iter()
+            .enumerate()
+            .map(|(i, def)| (def.parameter_index, i))
+            .collect();
     }
 
     fn parse_type_hierarchy(&mut self, gm: &'a brocolib::global_metadata::GlobalMetadata) {
@@ -222,4 +370,20 @@ impl<'a> CordlMetadata<'a> {
             })
             .collect();
     }
+
+    fn parse_custom_attribute_ranges(&mut self, gm: &brocolib::global_metadata::GlobalMetadata) {
+        self.custom_attribute_ranges = gm
+            .images
+            .as_vec()
+            .iter()
+            .flat_map(|img| {
+                let start = img.custom_attribute_start as usize;
+                let range = start..start + img.custom_attribute_count as usize;
+
+                let type_start = img.type_start.index();
+                (type_start..type_start + img.type_count)
+                    .map(move |tdi| (TypeDefinitionIndex::new(tdi), range.clone()))
+            })
+            .collect();
+    }
 }
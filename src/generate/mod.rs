@@ -1,16 +1,31 @@
 pub mod context;
 pub mod cs_context_collection;
 pub mod cs_members;
+pub mod cs_name_resolver;
 pub mod cs_type;
 pub mod cs_type_tag;
+pub mod custom_attributes;
+pub mod graph;
 pub mod metadata;
 pub mod offsets;
+pub mod output;
+pub mod report;
 pub mod type_extensions;
 pub mod writer;
 
+#[cfg(feature = "c_header")]
+pub mod c_header;
 #[cfg(feature = "cpp")]
 pub mod cpp;
+#[cfg(feature = "csharp")]
+pub mod csharp;
+#[cfg(feature = "docs")]
+pub mod docs;
+#[cfg(feature = "frida")]
+pub mod frida;
 #[cfg(feature = "json")]
 pub mod json;
 #[cfg(feature = "rust")]
 pub mod rust;
+#[cfg(feature = "symbols")]
+pub mod symbols;
@@ -9,6 +9,7 @@ use brocolib::global_metadata::Il2CppTypeDefinition;
 use brocolib::runtime_metadata::Il2CppTypeDefinitionSizes;
 use brocolib::runtime_metadata::TypeData;
 use brocolib::runtime_metadata::{Il2CppType, Il2CppTypeEnum};
+use color_eyre::eyre::{bail, ensure, eyre};
 use itertools::Itertools;
 use log::debug;
 
@@ -35,12 +36,13 @@ pub fn get_size_info<'a>(
     tdi: TypeDefinitionIndex,
     generic_inst_types: Option<&[usize]>,
     metadata: &'a CordlMetadata,
-) -> SizeInfo {
-    let size_metadata = get_size_of_type_table(metadata, tdi).unwrap();
+) -> color_eyre::Result<SizeInfo> {
+    let size_metadata = get_size_of_type_table(metadata, tdi)
+        .ok_or_else(|| eyre!("No size table entry for {tdi:?}"))?;
     let mut instance_size = size_metadata.instance_size;
     let mut native_size = size_metadata.native_size;
 
-    let sa = layout_fields(metadata, t, tdi, generic_inst_types, None, true);
+    let sa = layout_fields(metadata, t, tdi, generic_inst_types, None, true)?;
     let mut calculated_instance_size = sa.size;
 
     let minimum_alignment = sa.alignment;
@@ -54,16 +56,20 @@ pub fn get_size_info<'a>(
     if t.is_value_type() || t.is_enum_type() {
         instance_size = instance_size
             .checked_sub(metadata.object_size() as u32)
-            .unwrap();
+            .ok_or_else(|| eyre!("Instance size {instance_size} is smaller than an object"))?;
         calculated_instance_size = calculated_instance_size
             .checked_sub(metadata.object_size() as usize)
-            .unwrap();
+            .ok_or_else(|| {
+                eyre!(
+                    "Calculated instance size {calculated_instance_size} is smaller than an object"
+                )
+            })?;
     }
 
     let packing = get_type_def_packing(metadata, t);
     let specified_packing = get_packing(metadata, t);
 
-    SizeInfo {
+    Ok(SizeInfo {
         instance_size,
         calculated_instance_size: calculated_instance_size as u32,
 
@@ -73,7 +79,7 @@ pub fn get_size_info<'a>(
         calculated_native_size: sa.actual_size as i32,
         packing,
         specified_packing,
-    }
+    })
 }
 
 pub fn get_size_and_packing<'a>(
@@ -81,31 +87,32 @@ pub fn get_size_and_packing<'a>(
     tdi: TypeDefinitionIndex,
     generic_inst_types: Option<&[usize]>,
     metadata: &'a CordlMetadata,
-) -> (u32, Option<u8>) {
-    let size_metadata = get_size_of_type_table(metadata, tdi).unwrap();
+) -> color_eyre::Result<(u32, Option<u8>)> {
+    let size_metadata = get_size_of_type_table(metadata, tdi)
+        .ok_or_else(|| eyre!("No size table entry for {tdi:?}"))?;
     let mut metadata_size = size_metadata.instance_size;
 
     if metadata_size == 0 && !t.is_interface() {
-        let sa = layout_fields(metadata, t, tdi, generic_inst_types, None, true);
+        let sa = layout_fields(metadata, t, tdi, generic_inst_types, None, true)?;
         metadata_size = sa.size.try_into().unwrap();
     }
 
     if t.is_value_type() || t.is_enum_type() {
         metadata_size = metadata_size
             .checked_sub(metadata.object_size() as u32)
-            .unwrap()
+            .ok_or_else(|| eyre!("Instance size {metadata_size} is smaller than an object"))?;
     }
 
     let packing = get_packing(metadata, t);
 
-    (metadata_size, packing)
+    Ok((metadata_size, packing))
 }
 
 pub fn get_il2cpptype_sa(
     metadata: &CordlMetadata<'_>,
     ty: &Il2CppType,
     generic_inst_types: Option<&[usize]>,
-) -> SizeAndAlignment {
+) -> color_eyre::Result<SizeAndAlignment> {
     get_type_size_and_alignment(ty, generic_inst_types, metadata)
 }
 
@@ -114,8 +121,9 @@ pub fn get_sizeof_type<'a>(
     tdi: TypeDefinitionIndex,
     generic_inst_types: Option<&[usize]>,
     metadata: &'a CordlMetadata,
-) -> u32 {
-    let size_metadata = get_size_of_type_table(metadata, tdi).unwrap();
+) -> color_eyre::Result<u32> {
+    let size_metadata = get_size_of_type_table(metadata, tdi)
+        .ok_or_else(|| eyre!("No size table entry for {tdi:?}"))?;
     let mut metadata_size = size_metadata.instance_size;
 
     if metadata_size == 0 && !t.is_interface() {
@@ -123,7 +131,7 @@ pub fn get_sizeof_type<'a>(
             "Computing instance size by laying out type for tdi: {tdi:?} {}",
             t.full_name(metadata.metadata, true)
         );
-        metadata_size = layout_fields(metadata, t, tdi, generic_inst_types, None, true)
+        metadata_size = layout_fields(metadata, t, tdi, generic_inst_types, None, true)?
             .size
             .try_into()
             .unwrap();
@@ -134,20 +142,20 @@ pub fn get_sizeof_type<'a>(
         // For value types we need to ALWAYS subtract our object size
         metadata_size = metadata_size
             .checked_sub(metadata.object_size() as u32)
-            .unwrap();
+            .ok_or_else(|| eyre!("Instance size {metadata_size} is smaller than an object"))?;
         debug!(
             "Resulting computed instance size (post subtracting) for type {:?} is: {}",
             t.full_name(metadata.metadata, true),
             metadata_size
         );
 
-        // If we are still 0, todo!
+        // If we are still 0, bail
         if metadata_size == 0 {
-            todo!("We do not yet support cases where the instance type would be a 0 AFTER we have done computation! type: {}", t.full_name(metadata.metadata, true));
+            bail!("We do not yet support cases where the instance type would be a 0 AFTER we have done computation! type: {}", t.full_name(metadata.metadata, true));
         }
     }
 
-    metadata_size
+    Ok(metadata_size)
 }
 
 const PACKING_SIZE_ZERO: u32 = 0;
@@ -234,7 +242,7 @@ pub fn layout_fields(
     generic_inst_types: Option<&[usize]>,
     offsets: Option<&mut Vec<u32>>,
     strictly_calculated: bool,
-) -> SizeAndAlignment {
+) -> color_eyre::Result<SizeAndAlignment> {
     let mut instance_size: usize;
     let mut actual_size: usize;
 
@@ -244,7 +252,7 @@ pub fn layout_fields(
     // packing calculation based on RuntimeType::GetPacking
     let packing = get_type_def_packing(metadata, declaring_ty_def);
 
-    assert!(
+    ensure!(
         packing.unwrap_or_default() <= 128,
         "Packing must be valid! Actual: {packing:?}",
     );
@@ -255,7 +263,7 @@ pub fn layout_fields(
         actual_size = metadata.object_size() as usize;
         minimum_alignment = metadata.pointer_size as u8;
     } else {
-        let parent_sa = get_parent_sa(metadata, declaring_ty_def.parent_index, generic_inst_types);
+        let parent_sa = get_parent_sa(metadata, declaring_ty_def.parent_index, generic_inst_types)?;
 
         instance_size = parent_sa.size;
         actual_size = parent_sa.actual_size;
@@ -283,7 +291,7 @@ pub fn layout_fields(
                 natural_alignment,
                 packing,
             },
-        );
+        )?;
 
         let mut offsets_opt = offsets;
         if let Some(offsets) = offsets_opt.as_mut() {
@@ -329,13 +337,13 @@ pub fn layout_fields(
         }
     }
 
-    SizeAndAlignment {
+    Ok(SizeAndAlignment {
         size: instance_size,
         actual_size,
         alignment: minimum_alignment,
         natural_alignment,
         packing,
-    }
+    })
 }
 
 /// equivalent to libil2cpp FieldLayout::LayoutFields with the instance field filter
@@ -346,7 +354,7 @@ fn layout_instance_fields(
     generic_inst_types: Option<&[usize]>,
     offsets: Option<&mut Vec<u32>>,
     parent_sa: SizeAndAlignment,
-) -> SizeAndAlignment {
+) -> color_eyre::Result<SizeAndAlignment> {
     let parent_size = parent_sa.size;
     let actual_parent_size = parent_sa.actual_size;
     let parent_alignment = parent_sa.alignment;
@@ -374,7 +382,7 @@ fn layout_instance_fields(
             continue;
         }
 
-        let sa = get_type_size_and_alignment(field_ty, generic_inst_types, metadata);
+        let sa = get_type_size_and_alignment(field_ty, generic_inst_types, metadata)?;
         let mut alignment = sa.alignment;
         if alignment < 4 && sa.natural_alignment != 0 {
             alignment = sa.natural_alignment;
@@ -412,13 +420,13 @@ fn layout_instance_fields(
 
     instance_size = align_to(actual_size, minimum_alignment as usize);
 
-    SizeAndAlignment {
+    Ok(SizeAndAlignment {
         size: instance_size,
         actual_size,
         alignment: minimum_alignment,
         natural_alignment,
         packing,
-    }
+    })
 }
 
 fn get_offset_of_type_table(
@@ -443,7 +451,7 @@ fn get_parent_sa(
     metadata: &CordlMetadata<'_>,
     parent_index: u32,
     generic_inst_types: Option<&[usize]>,
-) -> SizeAndAlignment {
+) -> color_eyre::Result<SizeAndAlignment> {
     let parent_ty = &metadata.metadata_registration.types[parent_index as usize];
     let (parent_tdi, parent_generics) = match parent_ty.data {
         TypeData::TypeDefinitionIndex(parent_tdi) => (parent_tdi, None),
@@ -454,12 +462,15 @@ fn get_parent_sa(
                 .metadata_registration
                 .generic_classes[generic_index];
 
-            let generic_inst = &metadata.metadata_registration.generic_insts
-                [generic_class.context.class_inst_idx.unwrap()];
+            let class_inst_idx = generic_class
+                .context
+                .class_inst_idx
+                .ok_or_else(|| eyre!("Generic parent {generic_index} has no class inst"))?;
+            let generic_inst = &metadata.metadata_registration.generic_insts[class_inst_idx];
 
             let generic_ty = &metadata.metadata_registration.types[generic_class.type_index];
             let TypeData::TypeDefinitionIndex(parent_tdi) = generic_ty.data else {
-                panic!(
+                bail!(
                     "Failed to find TypeDefinitionIndex for generic class: {:?}",
                     generic_ty.data
                 );
@@ -492,7 +503,7 @@ fn get_parent_sa(
 
             (parent_tdi, Some(true_generics))
         }
-        _ => todo!("Not yet implemented: {:?}", parent_ty.data),
+        _ => bail!("Unsupported parent type: {:?}", parent_ty.data),
     };
 
     layout_fields(
@@ -578,7 +589,7 @@ fn get_type_size_and_alignment(
     ty: &Il2CppType,
     generic_inst_types: Option<&[usize]>,
     metadata: &CordlMetadata,
-) -> SizeAndAlignment {
+) -> color_eyre::Result<SizeAndAlignment> {
     let mut sa = SizeAndAlignment {
         alignment: 0,
         natural_alignment: 0,
@@ -590,7 +601,7 @@ fn get_type_size_and_alignment(
     if ty.byref && !ty.valuetype {
         sa.size = metadata.pointer_size as usize;
         sa.alignment = get_alignment_of_type(OffsetType::Pointer, metadata.pointer_size);
-        return sa;
+        return Ok(sa);
     }
 
     // only handle if generic inst, otherwise let the rest handle it as before
@@ -668,7 +679,7 @@ fn get_type_size_and_alignment(
         }
         Il2CppTypeEnum::Valuetype => {
             let TypeData::TypeDefinitionIndex(value_tdi) = ty.data else {
-                panic!(
+                bail!(
                     "Failed to find a valid TypeDefinitionIndex from type's data: {:?}",
                     ty.data
                 )
@@ -685,7 +696,7 @@ fn get_type_size_and_alignment(
             // The way we compute the instance size is by grabbing the TD and performing a full field walk over that type
             // Specifically, we call: layout_fields_for_type
             // TODO: We should cache this call
-            let res = layout_fields(metadata, value_td, value_tdi, None, None, false);
+            let res = layout_fields(metadata, value_td, value_tdi, None, None, false)?;
             sa.size = res.size - metadata.object_size() as usize;
             sa.actual_size = res.actual_size;
             sa.alignment = res.alignment;
@@ -694,20 +705,27 @@ fn get_type_size_and_alignment(
         }
         Il2CppTypeEnum::Genericinst => {
             let TypeData::GenericClassIndex(gtype) = ty.data else {
-                panic!(
+                bail!(
                     "Failed to find a valid GenericClassIndex from type's data: {:?}",
                     ty.data
                 )
             };
             let mr = &metadata.metadata_registration;
-            let generic_class = mr.generic_classes.get(gtype).unwrap();
+            let generic_class = mr
+                .generic_classes
+                .get(gtype)
+                .ok_or_else(|| eyre!("No generic class at index {gtype}"))?;
+            let class_inst_idx = generic_class
+                .context
+                .class_inst_idx
+                .ok_or_else(|| eyre!("Generic class {gtype} has no class inst"))?;
 
-            let new_generic_inst = &mr.generic_insts[generic_class.context.class_inst_idx.unwrap()];
+            let new_generic_inst = &mr.generic_insts[class_inst_idx];
 
             let generic_type_def = &mr.types[generic_class.type_index];
 
             let TypeData::TypeDefinitionIndex(tdi) = generic_type_def.data else {
-                panic!(
+                bail!(
                     "Failed to find a valid TypeDefinitionIndex from type's data: {:?}",
                     generic_type_def.data
                 )
@@ -718,7 +736,7 @@ fn get_type_size_and_alignment(
             if !td.is_value_type() && !td.is_enum_type() {
                 sa.size = metadata.pointer_size as usize;
                 sa.alignment = get_alignment_of_type(OffsetType::Pointer, metadata.pointer_size);
-                return sa;
+                return Ok(sa);
             }
 
             // enum type
@@ -770,7 +788,7 @@ fn get_type_size_and_alignment(
                 Some(&new_generic_inst_types),
                 None,
                 false,
-            );
+            )?;
             sa.size = res.size - metadata.object_size() as usize;
             sa.actual_size = res.actual_size;
             sa.alignment = res.alignment;
@@ -779,14 +797,14 @@ fn get_type_size_and_alignment(
             // sa.natural_alignment = res.natural_alignment;
         }
         _ => {
-            panic!(
+            bail!(
                 "Failed to compute type size and alignment of type: {:?}",
                 ty
             );
         }
     }
 
-    sa
+    Ok(sa)
 }
 
 fn align_to(size: usize, alignment: usize) -> usize {
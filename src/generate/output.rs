@@ -0,0 +1,178 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use color_eyre::eyre::Context;
+use log::{info, warn};
+use pathdiff::diff_paths;
+
+use crate::helpers::hash::stable_hash;
+
+/// Stores the hash of every file written by the previous run, relative to the output root
+const MANIFEST_FILE: &str = ".cordl_hashes.json";
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OutputStats {
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    pub deleted: usize,
+}
+
+impl Display for OutputStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} created, {} updated, {} unchanged, {} deleted",
+            self.created, self.updated, self.unchanged, self.deleted
+        )
+    }
+}
+
+///
+/// Writes generated files to an output folder, leaving files whose content did not change untouched
+/// so build systems do not recompile them.
+/// Files written by the previous run but not by this one are deleted by `finish`,
+/// other files under the root are left alone
+///
+pub struct OutputFiles {
+    root: PathBuf,
+    previous: BTreeMap<PathBuf, u64>,
+    written: Mutex<BTreeMap<PathBuf, u64>>,
+    changed: Mutex<Vec<PathBuf>>,
+
+    created: AtomicUsize,
+    updated: AtomicUsize,
+    unchanged: AtomicUsize,
+}
+
+impl OutputFiles {
+    pub fn new(root: &Path) -> color_eyre::Result<Self> {
+        fs::create_dir_all(root)?;
+
+        let manifest_path = root.join(MANIFEST_FILE);
+        let previous = match fs::read(&manifest_path) {
+            Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+                warn!("Ignoring invalid manifest {manifest_path:?}: {e}");
+                Default::default()
+            }),
+            Err(_) => Default::default(),
+        };
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            previous,
+            written: Default::default(),
+            changed: Default::default(),
+            created: Default::default(),
+            updated: Default::default(),
+            unchanged: Default::default(),
+        })
+    }
+
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        diff_paths(path, &self.root).unwrap_or_else(|| path.to_path_buf())
+    }
+
+    ///
+    /// Writes `content` to `path` unless the file already holds it.
+    /// The hash of the previous run is trusted if present, otherwise the file on disk is hashed
+    ///
+    pub fn write(&self, path: &Path, content: &[u8]) -> color_eyre::Result<()> {
+        let relative_path = self.relative_path(path);
+        let hash = stable_hash(content);
+
+        let exists = path.is_file();
+        let old_hash = match self.previous.get(&relative_path) {
+            Some(old_hash) if exists => Some(*old_hash),
+            _ if exists => fs::read(path).ok().map(|data| stable_hash(&data)),
+            _ => None,
+        };
+
+        self.written.lock().unwrap().insert(relative_path, hash);
+
+        if old_hash == Some(hash) {
+            self.unchanged.fetch_add(1, Ordering::Relaxed);
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content).with_context(|| format!("Failed to write {path:?}"))?;
+
+        match exists {
+            true => self.updated.fetch_add(1, Ordering::Relaxed),
+            false => self.created.fetch_add(1, Ordering::Relaxed),
+        };
+        self.changed.lock().unwrap().push(path.to_path_buf());
+
+        Ok(())
+    }
+
+    /// Whether `path` was written by this run, or is a folder containing such files
+    pub fn is_written(&self, path: &Path) -> bool {
+        let relative_path = self.relative_path(path);
+        let written = self.written.lock().unwrap();
+
+        // paths are ordered by component, so the contents of a folder directly follow it
+        written
+            .range(relative_path.clone()..)
+            .next()
+            .is_some_and(|(p, _)| p.starts_with(&relative_path))
+    }
+
+    /// Files created or updated by this run
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        self.changed.lock().unwrap().clone()
+    }
+
+    ///
+    /// Deletes the files the previous run wrote but this one did not, and the folders that leaves empty.
+    /// Stores the hashes for the next run
+    ///
+    pub fn finish(self) -> color_eyre::Result<OutputStats> {
+        let written = self.written.into_inner().unwrap();
+        let manifest_path = self.root.join(MANIFEST_FILE);
+
+        let orphans: Vec<PathBuf> = self
+            .previous
+            .keys()
+            .filter(|p| !written.contains_key(*p))
+            .map(|p| self.root.join(p))
+            .filter(|p| p.is_file())
+            .collect();
+
+        for orphan in &orphans {
+            info!("Deleting {orphan:?}");
+            fs::remove_file(orphan)?;
+        }
+
+        // remove the folders emptied by the deletions, up to the root
+        let emptied: BTreeSet<&Path> = orphans.iter().filter_map(|p| p.parent()).collect();
+        for folder in emptied.into_iter().rev() {
+            for folder in folder.ancestors().take_while(|f| *f != self.root) {
+                // fails if not empty
+                if fs::remove_dir(folder).is_err() {
+                    break;
+                }
+            }
+        }
+
+        fs::write(&manifest_path, serde_json::to_vec_pretty(&written)?)?;
+
+        Ok(OutputStats {
+            created: self.created.into_inner(),
+            updated: self.updated.into_inner(),
+            unchanged: self.unchanged.into_inner(),
+            deleted: orphans.len(),
+        })
+    }
+}
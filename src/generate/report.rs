@@ -0,0 +1,268 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
+    io::BufWriter,
+    path::Path,
+};
+
+use brocolib::global_metadata::TypeDefinitionIndex;
+use itertools::Itertools;
+use log::info;
+use serde::Serialize;
+
+use crate::data::profile::BlacklistPatternKind;
+
+use super::{
+    cs_context_collection::TypeContextCollection,
+    cs_members::CsFieldOffsetSource,
+    cs_type::CsType,
+    cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata,
+    type_extensions::{MethodDefintionExtensions, TypeDefinitionExtensions},
+};
+
+///
+/// Summary of what a run generated and skipped, written to `cordl-report.json`.
+/// Entries are sorted so reports of two game versions can be diffed
+///
+#[derive(Debug, Default, Serialize)]
+pub struct GenerationReport {
+    pub counts: ReportCounts,
+
+    /// Types blacklisted by the profile
+    pub blacklisted_types: Vec<String>,
+    /// Types which failed to generate and were blacklisted
+    pub failed_types: Vec<FailedType>,
+    /// Types cordl did not make a C# type for, such as ones without a parent
+    pub skipped_types: Vec<String>,
+    pub unmatched_blacklist_patterns: Vec<UnmatchedPattern>,
+
+    /// Instance fields whose offset was read from the metadata table instead of laid out
+    pub fields_with_table_offsets: Vec<String>,
+    /// Instance fields of reference types placed inside the object header
+    pub fields_inside_object_header: Vec<String>,
+    /// Concrete methods without an address.
+    /// Methods of generic type definitions and generic methods are left out as they never have one
+    pub methods_without_addresses: Vec<String>,
+
+    /// Full names shared by more than one type definition
+    pub type_name_collisions: Vec<NameCollision>,
+    /// Names used by more than one kind of member of a type e.g a field and a method
+    pub member_name_collisions: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ReportCounts {
+    pub types_generated: usize,
+    pub types_blacklisted: usize,
+    pub types_failed: usize,
+    pub types_skipped: usize,
+    pub unmatched_blacklist_patterns: usize,
+    pub fields_with_computed_offsets: usize,
+    pub fields_with_table_offsets: usize,
+    pub fields_inside_object_header: usize,
+    pub methods_with_addresses: usize,
+    pub methods_without_addresses: usize,
+    pub type_name_collisions: usize,
+    pub member_name_collisions: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedType {
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UnmatchedPattern {
+    pub kind: BlacklistPatternKind,
+    pub pattern: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NameCollision {
+    pub name: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MemberKind {
+    Field,
+    Property,
+    Method,
+}
+
+pub fn make_report(
+    metadata: &CordlMetadata,
+    collection: &TypeContextCollection,
+) -> GenerationReport {
+    let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+    let td_name = |tdi: TypeDefinitionIndex| {
+        type_defs[tdi.index() as usize].full_name(metadata.metadata, true)
+    };
+
+    let mut report = GenerationReport {
+        blacklisted_types: metadata
+            .blacklisted_types
+            .iter()
+            .filter(|tdi| !metadata.failed_types.contains_key(tdi))
+            .map(|tdi| td_name(*tdi))
+            .sorted()
+            .collect(),
+        failed_types: metadata
+            .failed_types
+            .iter()
+            .map(|(tdi, error)| FailedType {
+                name: td_name(*tdi),
+                error: error.clone(),
+            })
+            .sorted_by(|a, b| a.name.cmp(&b.name))
+            .collect(),
+        unmatched_blacklist_patterns: metadata
+            .unmatched_blacklist_patterns
+            .iter()
+            .map(|(kind, pattern)| UnmatchedPattern {
+                kind: *kind,
+                pattern: pattern.clone(),
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    report.skipped_types = (0..type_defs.len())
+        .map(|i| TypeDefinitionIndex::new(i as u32))
+        .filter(|tdi| {
+            !metadata.blacklisted_types.contains(tdi)
+                && collection
+                    .get_cs_type(CsTypeTag::TypeDefinitionIndex(*tdi))
+                    .is_none()
+        })
+        .map(td_name)
+        .sorted()
+        .collect();
+
+    report.type_name_collisions = type_defs
+        .iter()
+        .map(|td| td.full_name(metadata.metadata, true))
+        .counts()
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(name, count)| NameCollision { name, count })
+        .sorted_by(|a, b| a.name.cmp(&b.name))
+        .collect();
+
+    let cs_types = collection
+        .get()
+        .values()
+        .flat_map(|c| c.get_types().values())
+        .map(|t| (t.cs_name_components.combine_all(), t))
+        .sorted_by(|a, b| a.0.cmp(&b.0))
+        .collect_vec();
+    for (full_name, ty) in &cs_types {
+        add_type(&mut report, metadata, full_name, ty);
+    }
+
+    report.counts = ReportCounts {
+        types_generated: cs_types.len(),
+        types_blacklisted: report.blacklisted_types.len(),
+        types_failed: report.failed_types.len(),
+        types_skipped: report.skipped_types.len(),
+        unmatched_blacklist_patterns: report.unmatched_blacklist_patterns.len(),
+        fields_with_table_offsets: report.fields_with_table_offsets.len(),
+        fields_inside_object_header: report.fields_inside_object_header.len(),
+        methods_without_addresses: report.methods_without_addresses.len(),
+        type_name_collisions: report.type_name_collisions.len(),
+        member_name_collisions: report.member_name_collisions.len(),
+        ..report.counts
+    };
+
+    report
+}
+
+fn add_type(report: &mut GenerationReport, metadata: &CordlMetadata, full_name: &str, ty: &CsType) {
+    for field in ty.fields.iter().filter(|f| f.instance) {
+        let entry = format!("{full_name}::{}", field.name);
+
+        match field.offset_source {
+            Some(CsFieldOffsetSource::Computed) => report.counts.fields_with_computed_offsets += 1,
+            Some(CsFieldOffsetSource::Table) => {
+                report.fields_with_table_offsets.push(entry.clone())
+            }
+            None => {}
+        }
+
+        if !ty.is_value_type
+            && !ty.is_enum_type
+            && field
+                .offset
+                .is_some_and(|offset| offset < metadata.object_size() as u32)
+        {
+            report.fields_inside_object_header.push(entry);
+        }
+    }
+
+    let is_generic_template =
+        ty.generic_template.is_some() && ty.generic_instantiations_args_types.is_none();
+    for method in &ty.methods {
+        let method_def = &metadata.metadata.global_metadata.methods[method.method_index];
+        if is_generic_template || method.template.is_some() || method_def.is_abstract_method() {
+            continue;
+        }
+
+        match method.method_data.address() {
+            Some(_) => report.counts.methods_with_addresses += 1,
+            None => report
+                .methods_without_addresses
+                .push(format!("{full_name}::{}", method.name)),
+        }
+    }
+
+    let mut members: BTreeMap<&str, BTreeSet<MemberKind>> = BTreeMap::new();
+    let named_members = ty
+        .fields
+        .iter()
+        .map(|f| (f.name.as_str(), MemberKind::Field))
+        .chain(
+            ty.properties
+                .iter()
+                .map(|p| (p.name.as_str(), MemberKind::Property)),
+        )
+        .chain(
+            ty.methods
+                .iter()
+                .map(|m| (m.name.as_str(), MemberKind::Method)),
+        );
+    for (name, kind) in named_members {
+        members.entry(name).or_default().insert(kind);
+    }
+    report.member_name_collisions.extend(
+        members
+            .into_iter()
+            .filter(|(_, kinds)| kinds.len() > 1)
+            .map(|(name, _)| format!("{full_name}::{name}")),
+    );
+}
+
+pub fn write_report(
+    report: &GenerationReport,
+    file: &Path,
+    format: bool,
+) -> color_eyre::Result<()> {
+    info!(
+        "Writing report to {}: {} types generated, {} blacklisted, {} failed, {} skipped",
+        file.display(),
+        report.counts.types_generated,
+        report.counts.types_blacklisted,
+        report.counts.types_failed,
+        report.counts.types_skipped
+    );
+
+    let mut buf_writer = BufWriter::new(File::create(file)?);
+
+    match format {
+        true => serde_json::to_writer_pretty(&mut buf_writer, report)?,
+        false => serde_json::to_writer(&mut buf_writer, report)?,
+    };
+
+    Ok(())
+}
@@ -1,16 +1,20 @@
-use std::{path::PathBuf, sync::LazyLock};
-
-pub static STATIC_CONFIG: LazyLock<RustGenerationConfig> = LazyLock::new(|| RustGenerationConfig {
-    source_path: PathBuf::from("./codegen-rs/src"),
-    cargo_config: PathBuf::from("./codegen-rs/Cargo.toml"),
-});
+use std::path::{Path, PathBuf};
 
 pub struct RustGenerationConfig {
     pub source_path: PathBuf,
     pub cargo_config: PathBuf,
+    pub crate_name: String,
 }
 
 impl RustGenerationConfig {
+    pub fn new(out_dir: &Path, crate_name: &str) -> Self {
+        RustGenerationConfig {
+            source_path: out_dir.join("src"),
+            cargo_config: out_dir.join("Cargo.toml"),
+            crate_name: crate_name.to_string(),
+        }
+    }
+
     pub fn namespace_rs(&self, string: &str) -> String {
         let final_ns = if string.is_empty() {
             "GlobalNamespace".to_owned()
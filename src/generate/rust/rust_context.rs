@@ -1,17 +1,16 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs::{create_dir_all, File},
-    io::BufWriter,
-    path::{self, PathBuf},
+    path::{self, Path, PathBuf},
 };
 
-use color_eyre::eyre::ContextCompat;
+use color_eyre::eyre::Context;
 use itertools::Itertools;
 use log::{trace, warn};
 use std::io::Write;
 
 use crate::generate::{
-    cs_type_tag::CsTypeTag, type_extensions::TypeDefinitionExtensions, writer::Writer,
+    cs_type_tag::CsTypeTag, output::OutputFiles, type_extensions::TypeDefinitionExtensions,
+    writer::Writer,
 };
 
 use super::rust_type::RustType;
@@ -109,29 +108,20 @@ impl RustContext {
         &mut self.typedef_types
     }
 
-    pub(crate) fn write(
+    ///
+    /// The unformatted source of the context's types.
+    /// The syn tokens are neither Send nor Sync, so they are rendered to text
+    /// which `write_source` parses and formats on any thread
+    ///
+    pub(crate) fn render(
         &self,
         config: &super::config::RustGenerationConfig,
-    ) -> Result<(), color_eyre::eyre::Error> {
+    ) -> Result<String, color_eyre::eyre::Error> {
         let _base_path = &config.source_path;
 
-        if !self
-            .fundamental_path
-            .parent()
-            .context("parent is not a directory!")?
-            .is_dir()
-        {
-            // Assume it's never a file
-            create_dir_all(
-                self.fundamental_path
-                    .parent()
-                    .context("Failed to create all directories!")?,
-            )?;
-        }
-
-        trace!("Writing {:?}", self.fundamental_path.as_path());
+        trace!("Rendering {:?}", self.fundamental_path.as_path());
         let mut typedef_writer = Writer {
-            stream: BufWriter::new(File::create(self.fundamental_path.as_path())?),
+            stream: Vec::new(),
             indent: 0,
             newline: true,
         };
@@ -160,7 +150,20 @@ impl RustContext {
             t.write(&mut typedef_writer, config)?;
         }
 
-        Ok(())
+        Ok(String::from_utf8(typedef_writer.stream)?)
+    }
+
+    /// Formats the source made by `render` and writes it to `path`
+    pub(crate) fn write_source(
+        path: &Path,
+        source: &str,
+        output: &OutputFiles,
+    ) -> Result<(), color_eyre::eyre::Error> {
+        let syntax_tree = syn::parse_file(source)
+            .with_context(|| format!("Unable to parse the source of {}", path.display()))?;
+        let formatted = prettyplease::unparse(&syntax_tree);
+
+        output.write(path, formatted.as_bytes())
     }
 
     pub(crate) fn insert_rust_type(&mut self, new_rs_ty: RustType) {
@@ -1,8 +1,8 @@
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsStr,
-    fs::File,
-    io::{BufWriter, Write},
+    io::Write,
+    path::Path,
 };
 
 use rayon::prelude::*;
@@ -14,7 +14,7 @@ use walkdir::WalkDir;
 
 use crate::generate::{
     cs_context_collection::TypeContextCollection, cs_type::CsType, cs_type_tag::CsTypeTag,
-    metadata::CordlMetadata,
+    metadata::CordlMetadata, output::OutputFiles,
 };
 
 use super::{
@@ -22,6 +22,9 @@ use super::{
     rust_name_resolver::RustNameResolver, rust_type::RustType,
 };
 
+/// Template of the generated crate's Cargo.toml
+const CARGO_TEMPLATE: &str = include_str!("../../../cordl_internals_rs/Cargo_template.toml");
+
 #[derive(Default)]
 pub struct RustContextCollection {
     // Should always be a TypeDefinitionIndex
@@ -206,24 +209,40 @@ impl RustContextCollection {
         &mut self.all_contexts
     }
 
-    pub fn write_all(&self, config: &RustGenerationConfig) -> color_eyre::Result<()> {
+    pub fn write_all(
+        &self,
+        config: &RustGenerationConfig,
+        output: &OutputFiles,
+    ) -> color_eyre::Result<()> {
         let amount = self.all_contexts.len() as f64;
-        self.all_contexts
-            .iter()
+        // the contexts hold syn tokens which can't be shared between threads,
+        // so they are rendered to text first and parsed, formatted and written in parallel
+        let sources: Vec<(&Path, String)> = self
+            .all_contexts
+            .values()
+            .map(|c| Ok((c.fundamental_path.as_path(), c.render(config)?)))
+            .collect::<color_eyre::Result<_>>()?;
+
+        sources
+            .into_par_iter()
             .enumerate()
-            .try_for_each(|(i, (_, c))| {
+            .try_for_each(|(i, (path, source))| {
                 trace!(
                     "Writing {:.4}% ({}/{}) {}",
                     (i as f64 / amount * 100.0),
                     i,
                     amount,
-                    c.fundamental_path.display(),
+                    path.display(),
                 );
-                c.write(config)
+                RustContext::write_source(path, &source, output)
             })
     }
 
-    pub fn write_feature_block(&self, config: &RustGenerationConfig) -> color_eyre::Result<()> {
+    pub fn write_feature_block(
+        &self,
+        config: &RustGenerationConfig,
+        output: &OutputFiles,
+    ) -> color_eyre::Result<()> {
         let dependency_graph: Vec<(&RustType, Vec<_>)> = self
             .all_contexts
             .values()
@@ -274,26 +293,27 @@ impl RustContextCollection {
             .sorted()
             .join("\n");
 
-        let mut cargo_config =
-            match std::fs::read_to_string("./cordl_internals_rs/Cargo_template.toml") {
-                Ok(content) => content,
-                Err(_) => {
-                    eprintln!("Failed to load file `./cordl_internals_rs/Cargo_template.toml`");
-                    return Err(color_eyre::eyre::eyre!("Failed to load Cargo template"));
-                }
-            };
+        let cargo_config = CARGO_TEMPLATE
+            .replace("#cordl_crate_name", &config.crate_name)
+            .replace("#cordl_features", &feature_block);
 
-        cargo_config = cargo_config.replace("#cordl_features", &feature_block);
-
-        let mut file = File::create(&config.cargo_config)?;
-        file.write_all(cargo_config.as_bytes())?;
+        output.write(&config.cargo_config, cargo_config.as_bytes())?;
 
         Ok(())
     }
 
-    pub fn write_namespace_modules(&self, config: &RustGenerationConfig) -> color_eyre::Result<()> {
+    pub fn write_namespace_modules(
+        &self,
+        config: &RustGenerationConfig,
+        output: &OutputFiles,
+    ) -> color_eyre::Result<()> {
         info!("Writing namespace modules!");
-        fn make_mod_dir(dir: &std::path::Path, name: &str) -> Result<(), color_eyre::eyre::Error> {
+        fn make_mod_dir(
+            dir: &std::path::Path,
+            name: &str,
+            header: &[u8],
+            output: &OutputFiles,
+        ) -> Result<(), color_eyre::eyre::Error> {
             if !dir.exists() {
                 return Ok(());
             }
@@ -307,18 +327,15 @@ impl RustContextCollection {
 
             // Sort so things don't break git diffs
             modules_paths.sort();
+            // stale modules of previous runs are deleted once everything is written
+            modules_paths.retain(|m| output.is_written(m));
 
-            if modules_paths.is_empty() {
+            if modules_paths.is_empty() && header.is_empty() {
                 return Ok(());
             }
 
             let mod_path = dir.join(name).with_extension("rs");
-            let mod_file = File::options()
-                .truncate(false)
-                .append(true)
-                .create(true)
-                .open(&mod_path)?;
-            let mut buf_writer = BufWriter::new(mod_file);
+            let mut buf_writer = header.to_vec();
 
             for module in &modules_paths {
                 if module == dir || *module == mod_path {
@@ -332,7 +349,7 @@ impl RustContextCollection {
                 let file_stem = module.file_stem().unwrap().to_string_lossy();
 
                 if module.is_dir() {
-                    make_mod_dir(module, "mod.rs")?;
+                    make_mod_dir(module, "mod.rs", &[], output)?;
                     writeln!(buf_writer, "// namespace {};", file_stem)?;
                     writeln!(buf_writer, "pub mod {};", file_stem)?;
                 } else if module.extension() == Some(OsStr::new("rs")) {
@@ -342,17 +359,12 @@ impl RustContextCollection {
                 }
             }
 
-            buf_writer.flush()?;
+            output.write(&mod_path, &buf_writer)?;
 
             Ok(())
         }
 
-        let mod_file = File::options()
-            .create(true)
-            .append(true)
-            .truncate(false)
-            .open(config.source_path.join("lib.rs"))?;
-        let mut buf_writer = BufWriter::new(mod_file);
+        let mut buf_writer = Vec::new();
         writeln!(
             buf_writer,
             "
@@ -372,9 +384,8 @@ impl RustContextCollection {
         #![allow(clippy::large_enum_variant)]
         "
         )?;
-        buf_writer.flush()?;
 
-        make_mod_dir(&config.source_path, "lib.rs")?;
+        make_mod_dir(&config.source_path, "lib.rs", &buf_writer, output)?;
 
         Ok(())
     }
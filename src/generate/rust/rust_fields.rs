@@ -357,7 +357,7 @@ pub(crate) fn handle_const_fields(
         .sorted_by(|a, b| a.1.name.cmp(&b.1.name))
         .collect_vec();
 
-    if cpp_type.is_enum_type {
+    if cpp_type.is_enum_type && !cpp_type.is_flags_enum {
         // enums cannot have multiple entries with the same value
         for f in fields
             .into_iter()
@@ -1,6 +1,6 @@
 use std::{collections::HashSet, ops::Deref, sync::Arc};
 
-use color_eyre::eyre::{Context, ContextCompat, Result};
+use color_eyre::eyre::{ContextCompat, Result};
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens};
@@ -9,10 +9,10 @@ use syn::parse_quote;
 use crate::{
     data::{
         name_components::NameComponents,
-        type_resolver::{ResolvedType, TypeUsage},
+        type_resolver::{ResolvedType, ResolvedTypeData, TypeUsage},
     },
     generate::{
-        cs_members::{CsConstructor, CsField, CsMethod, CsParam},
+        cs_members::{CsConstructor, CsEvent, CsField, CsMethod, CsParam, CsParamFlags},
         cs_type::CsType,
         cs_type_tag::{self, CsTypeTag},
         metadata::CordlMetadata,
@@ -90,6 +90,8 @@ pub struct RustType {
 
     pub is_value_type: bool,
     pub is_enum_type: bool,
+    /// `[System.Flags]` enums are written as a struct with bitwise operators
+    pub is_flags_enum: bool,
     pub is_reference_type: bool,
     pub is_interface: bool,
 
@@ -151,6 +153,7 @@ impl RustType {
 
             is_value_type: cs_type.is_value_type,
             is_enum_type: cs_type.is_enum_type,
+            is_flags_enum: cs_type.is_flags_enum(),
             is_reference_type: cs_type.is_reference_type,
             is_interface: cs_type.is_interface,
             parent: Default::default(),
@@ -187,6 +190,7 @@ impl RustType {
         self.make_fields(&cs_type.fields, name_resolver, config);
 
         self.make_methods(&cs_type.methods, name_resolver, config);
+        self.make_events(&cs_type.events, &cs_type.methods, name_resolver, config);
 
         // add phantom markers
         self.make_generics();
@@ -638,84 +642,112 @@ impl RustType {
                     i,
                 );
 
-                let m_ret_ty = name_resolver
-                    .resolve_name(self, &m.return_type, TypeUsage::ReturnType, true)
-                    .wrap_by_gc();
-                let m_ret_ty_ident = m_ret_ty.to_type_token();
-                let m_result_ty: syn::Type =
-                    parse_quote!(quest_hook::libil2cpp::Result<#m_ret_ty_ident>);
+                let rust_func = self.make_method(m, m_name_rs, name_resolver, config);
+                self.methods.push(rust_func.into());
+            }
+        }
+    }
 
-                let params = m
-                    .parameters
-                    .iter()
-                    .map(|p| self.make_parameter(p, name_resolver, config))
-                    .collect_vec();
+    fn make_method(
+        &mut self,
+        m: &CsMethod,
+        m_name_rs: String,
+        name_resolver: &RustNameResolver,
+        config: &RustGenerationConfig,
+    ) -> RustFunction {
+        let m_ret_ty = name_resolver
+            .resolve_name(self, &m.return_type, TypeUsage::ReturnType, true)
+            .wrap_by_gc();
+        let m_ret_ty_ident = m_ret_ty.to_type_token();
+        let m_result_ty: syn::Type = parse_quote!(quest_hook::libil2cpp::Result<#m_ret_ty_ident>);
 
-                let param_names = params.iter().map(|p| &p.name);
-                let param_types = params.iter().map(|p| &p.param_type);
+        let params = m
+            .parameters
+            .iter()
+            .map(|p| self.make_parameter(p, name_resolver, config))
+            .collect_vec();
 
-                let method_generics = m
-                    .template
-                    .as_ref()
-                    .map(|t| {
-                        t.just_names()
-                            .map(|g| -> RustGeneric {
-                                RustGeneric {
-                                    name: g.clone(),
-                                    bounds: vec![],
-                                }
-                            })
-                            .collect_vec()
+        let param_names = params.iter().map(|p| &p.name);
+        let param_types = params.iter().map(|p| &p.param_type);
+
+        let method_generics = m
+            .template
+            .as_ref()
+            .map(|t| {
+                t.just_names()
+                    .map(|g| -> RustGeneric {
+                        RustGeneric {
+                            name: g.clone(),
+                            bounds: vec![],
+                        }
                     })
-                    .unwrap_or_default();
+                    .collect_vec()
+            })
+            .unwrap_or_default();
 
-                let body = self.make_method_body(
-                    m,
-                    m_name,
-                    param_types,
-                    param_names,
-                    m_ret_ty_ident,
-                    None,
-                );
+        let body =
+            self.make_method_body(m, &m.name, param_types, param_names, m_ret_ty_ident, None);
 
-                let combined_generics = self
-                    .rs_name_components
-                    .generics
-                    .clone()
-                    .unwrap_or_default()
-                    .into_iter()
-                    .chain(method_generics.clone().into_iter())
-                    .map(|mut g| {
-                        // TODO: Add these bounds on demand
-                        let bounds = vec![
-                            "quest_hook::libil2cpp::Type".to_string(),
-                            "quest_hook::libil2cpp::Argument".to_owned(),
-                            "quest_hook::libil2cpp::Returned".to_owned(),
-                        ];
-
-                        g.bounds.extend(bounds);
-                        g
-                    })
-                    .map(|g| -> syn::GenericParam { g.to_token_stream() })
-                    .collect_vec();
+        let combined_generics = self
+            .rs_name_components
+            .generics
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .chain(method_generics.clone().into_iter())
+            .map(|mut g| {
+                // TODO: Add these bounds on demand
+                let bounds = vec![
+                    "quest_hook::libil2cpp::Type".to_string(),
+                    "quest_hook::libil2cpp::Argument".to_owned(),
+                    "quest_hook::libil2cpp::Returned".to_owned(),
+                ];
+
+                g.bounds.extend(bounds);
+                g
+            })
+            .map(|g| -> syn::GenericParam { g.to_token_stream() })
+            .collect_vec();
 
-                let where_clause: syn::WhereClause = parse_quote! {
-                    where #(#combined_generics),*
-                };
+        let where_clause: syn::WhereClause = parse_quote! {
+            where #(#combined_generics),*
+        };
 
-                let rust_func = RustFunction {
-                    name: format_ident!("{m_name_rs}"),
-                    body: Some(body),
-                    generics: method_generics,
-                    is_mut: m.instance,
-                    is_ref: m.instance,
-                    is_self: m.instance,
-                    params,
-                    where_clause: Some(where_clause),
-
-                    return_type: Some(m_result_ty),
-                    visibility: (Visibility::Public),
+        RustFunction {
+            name: format_ident!("{m_name_rs}"),
+            body: Some(body),
+            generics: method_generics,
+            is_mut: m.instance,
+            is_ref: m.instance,
+            is_self: m.instance,
+            params,
+            where_clause: Some(where_clause),
+
+            return_type: Some(m_result_ty),
+            visibility: (Visibility::Public),
+        }
+    }
+
+    fn make_events(
+        &mut self,
+        events: &[CsEvent],
+        methods: &[CsMethod],
+        name_resolver: &RustNameResolver,
+        config: &RustGenerationConfig,
+    ) {
+        for event in events {
+            let accessors = [("subscribe", &event.add), ("unsubscribe", &event.remove)];
+
+            for (prefix, accessor) in accessors {
+                let Some((method_index, _)) = accessor else {
+                    continue;
+                };
+                let Some(m) = methods.iter().find(|m| m.method_index == *method_index) else {
+                    continue;
                 };
+
+                let m_name_rs = config.name_rs(&format!("{prefix}_{}", event.name));
+                let rust_func = self.make_method(m, m_name_rs, name_resolver, config);
                 self.methods.push(rust_func.into());
             }
         }
@@ -810,9 +842,25 @@ impl RustType {
         name_resolver: &RustNameResolver<'_, '_>,
         config: &RustGenerationConfig,
     ) -> RustParam {
-        let p_ty = name_resolver
-            .resolve_name(self, &p.il2cpp_ty, TypeUsage::Parameter, true)
-            .wrap_by_gc();
+        let p_ty = match &p.il2cpp_ty.data {
+            // ref/out become &mut T, in becomes &T
+            ResolvedTypeData::ByRef(inner) | ResolvedTypeData::ByRefConst(inner)
+                if !p.modifiers.is_empty() =>
+            {
+                let inner_ty = name_resolver
+                    .resolve_name(self, inner, TypeUsage::Parameter, true)
+                    .wrap_by_gc()
+                    .with_ref();
+
+                match p.modifiers.contains(CsParamFlags::IN) {
+                    true => inner_ty,
+                    false => inner_ty.with_mut(),
+                }
+            }
+            _ => name_resolver
+                .resolve_name(self, &p.il2cpp_ty, TypeUsage::Parameter, true)
+                .wrap_by_gc(),
+        };
         // let p_il2cpp_ty = p.il2cpp_ty.get_type(name_resolver.cordl_metadata);
 
         let name_rs = config.name_rs(&p.name);
@@ -880,7 +928,8 @@ impl RustType {
                 &metadata.metadata_registration.types[declaring_td.declaring_type_index as usize];
 
             let declaring_tag =
-                cs_type_tag::CsTypeTag::from_type_data(declaring_ty.data, metadata.metadata);
+                cs_type_tag::CsTypeTag::from_type_data(declaring_ty.data, metadata.metadata)
+                    .expect("Declaring type is not a type definition");
 
             declaring_td = declaring_tag
                 .get_tdi()
@@ -987,7 +1036,7 @@ impl RustType {
             });
         }
 
-        writer.write_pretty_tokens(tokens)?;
+        writer.write_tokens(tokens)?;
 
         self.write_impl(writer, config)?;
         Ok(())
@@ -1020,6 +1069,10 @@ impl RustType {
             .wrap_err("No enum backing type found!")?
             .to_type_token();
 
+        if self.is_flags_enum {
+            return self.write_flags_enum_type(writer, backing_type);
+        }
+
         let name_ident = self.rs_name_components.to_name_ident();
         let path_ident = self.rs_name_components.to_type_path_token();
 
@@ -1056,13 +1109,106 @@ impl RustType {
             #impl_value
         };
 
-        writer.write_pretty_tokens(tokens)?;
+        writer.write_tokens(tokens)?;
 
         // self.write_impl(writer, config)?;
 
         Ok(())
     }
 
+    /// Rust enums cannot hold combined values, so flags are a transparent wrapper instead
+    fn write_flags_enum_type(&self, writer: &mut Writer, backing_type: syn::Type) -> Result<()> {
+        let name_ident = self.rs_name_components.to_name_ident();
+
+        let constants = self.constants.iter().map(|f| -> syn::ImplItemConst {
+            let name = &f.name;
+            let val = &f.value;
+
+            parse_quote! {
+                pub const #name: Self = Self(#val);
+            }
+        });
+
+        let impl_value = self.implement_value_type();
+
+        let feature = self.self_feature.as_ref().map(|f| {
+            let name = &f.name;
+            quote! {
+                #[cfg(feature = #name)]
+            }
+        });
+
+        let bit_ops = [
+            ("BitOr", quote!(|)),
+            ("BitAnd", quote!(&)),
+            ("BitXor", quote!(^)),
+        ]
+        .into_iter()
+        .map(|(op_trait, op)| {
+            let op_fn = format_ident!("{}", op_trait.to_lowercase());
+            let assign_fn = format_ident!("{}_assign", op_trait.to_lowercase());
+            let assign_trait = format_ident!("{op_trait}Assign");
+            let op_trait = format_ident!("{op_trait}");
+
+            quote! {
+                #feature
+                impl ::std::ops::#op_trait for #name_ident {
+                    type Output = Self;
+
+                    fn #op_fn(self, rhs: Self) -> Self {
+                        Self(self.0 #op rhs.0)
+                    }
+                }
+
+                #feature
+                impl ::std::ops::#assign_trait for #name_ident {
+                    fn #assign_fn(&mut self, rhs: Self) {
+                        self.0 = self.0 #op rhs.0;
+                    }
+                }
+            }
+        });
+
+        let tokens = quote! {
+            #feature
+            #[repr(transparent)]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct #name_ident(pub #backing_type);
+
+            #feature
+            #[allow(non_upper_case_globals)]
+            impl #name_ident {
+                #(#constants)*
+
+                pub const fn bits(self) -> #backing_type {
+                    self.0
+                }
+
+                /// Whether all bits of `flag` are set
+                pub const fn has_flag(self, flag: Self) -> bool {
+                    self.0 & flag.0 == flag.0
+                }
+            }
+
+            #(#bit_ops)*
+
+            #feature
+            impl ::std::ops::Not for #name_ident {
+                type Output = Self;
+
+                fn not(self) -> Self {
+                    Self(!self.0)
+                }
+            }
+
+            #impl_value
+        };
+
+        writer.write_tokens(tokens)?;
+
+        Ok(())
+    }
+
     fn write_value_type(&self, writer: &mut Writer, config: &RustGenerationConfig) -> Result<()> {
         let generics = self.get_generics(0);
         let generic_names = self.get_generics_unbound(0);
@@ -1131,7 +1277,7 @@ impl RustType {
             }
         };
 
-        writer.write_pretty_tokens(tokens)?;
+        writer.write_tokens(tokens)?;
 
         self.write_impl(writer, config)?;
 
@@ -1282,7 +1428,7 @@ impl RustType {
             #(#other_impls)*
         };
 
-        writer.write_pretty_tokens(tokens.to_token_stream())?;
+        writer.write_tokens(tokens.to_token_stream())?;
         Ok(())
     }
 
@@ -1365,7 +1511,7 @@ impl RustType {
             });
         }
 
-        writer.write_pretty_tokens(tokens)?;
+        writer.write_tokens(tokens)?;
 
         self.write_impl(writer, config)?;
 
@@ -1554,11 +1700,9 @@ impl RustType {
 }
 
 impl Writer {
-    pub(crate) fn write_pretty_tokens(&mut self, tokens: TokenStream) -> Result<()> {
-        let syntax_tree = syn::parse2(tokens.clone()).with_context(|| format!("{tokens}"))?;
-        let formatted = prettyplease::unparse(&syntax_tree);
-
-        self.stream.write_all(formatted.as_bytes())?;
+    /// Writes the tokens unformatted, `RustContext::write_source` formats the whole file
+    pub(crate) fn write_tokens(&mut self, tokens: TokenStream) -> Result<()> {
+        writeln!(self.stream, "{tokens}")?;
         Ok(())
     }
 }
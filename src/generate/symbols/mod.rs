@@ -0,0 +1,3 @@
+pub mod symbols_main;
+
+mod symbols_data;
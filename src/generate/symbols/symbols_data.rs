@@ -0,0 +1,170 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::generate::{
+    cs_context_collection::TypeContextCollection, cs_members::CsMethod,
+    cs_name_resolver::CsNameResolver, cs_type::CsType, cs_type_tag::CsTypeTag,
+    metadata::CordlMetadata,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolTable {
+    pub methods: Vec<MethodSymbol>,
+    pub types: Vec<TypeSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodSymbol {
+    /// Unique name usable as a disassembler label e.g `System.Object$$ToString`
+    pub name: String,
+    /// Full C# signature e.g `string System.Object::ToString()`
+    pub signature: String,
+    pub addr: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeSymbol {
+    /// Unique name usable as a disassembler struct name
+    pub name: String,
+    pub full_name: String,
+    pub size: u32,
+    pub is_value_type: bool,
+    pub fields: Vec<FieldSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldSymbol {
+    pub name: String,
+    pub ty: String,
+    pub offset: u32,
+    pub size: usize,
+}
+
+///
+/// Replaces characters disassemblers reject in names
+/// e.g `List<int>` -> `List_int_`
+///
+pub fn sanitize_symbol(name: &str) -> String {
+    name.chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$') {
+                true => c,
+                false => '_',
+            },
+        )
+        .collect()
+}
+
+/// Appends a counter to names that were already used
+fn make_unique(name: String, used: &mut HashMap<String, usize>) -> String {
+    let count = used.entry(name.clone()).or_default();
+    *count += 1;
+
+    match *count {
+        1 => name,
+        n => format!("{name}_{n}"),
+    }
+}
+
+pub fn make_symbol_table(
+    metadata: &CordlMetadata,
+    collection: &TypeContextCollection,
+    only: Option<&HashSet<CsTypeTag>>,
+) -> SymbolTable {
+    let name_resolver = CsNameResolver {
+        cordl_metadata: metadata,
+        collection,
+    };
+
+    // sorted so the output is stable between runs
+    let types = collection
+        .sorted_types(only)
+        .into_iter()
+        .map(|t| (t.cs_name_components.combine_all(), t))
+        .collect_vec();
+
+    let mut used_method_names = HashMap::new();
+    let methods = types
+        .iter()
+        .flat_map(|(full_name, t)| t.methods.iter().map(move |m| (full_name, m)))
+        .filter_map(|(full_name, m)| {
+            let addr = m.method_data.address()?;
+            let name = sanitize_symbol(&format!("{full_name}$${}", m.name));
+
+            Some(MethodSymbol {
+                name: make_unique(name, &mut used_method_names),
+                signature: method_signature(full_name, m, &name_resolver),
+                addr,
+                size: m
+                    .method_data
+                    .estimated_size
+                    .filter(|s| *s != 0 && *s != usize::MAX),
+            })
+        })
+        .collect_vec();
+
+    let mut used_type_names = HashMap::new();
+    let types = types
+        .iter()
+        .filter_map(|(full_name, t)| {
+            let fields = struct_fields(t, &name_resolver)?;
+            let name = sanitize_symbol(full_name);
+
+            Some(TypeSymbol {
+                name: make_unique(name, &mut used_type_names),
+                full_name: full_name.clone(),
+                size: t.size_info.as_ref()?.instance_size,
+                is_value_type: t.is_value_type || t.is_enum_type,
+                fields,
+            })
+        })
+        .collect_vec();
+
+    SymbolTable { methods, types }
+}
+
+fn method_signature(full_name: &str, method: &CsMethod, name_resolver: &CsNameResolver) -> String {
+    let params = method
+        .parameters
+        .iter()
+        .map(|p| format!("{} {}", name_resolver.resolve_name(&p.il2cpp_ty), p.name))
+        .join(", ");
+    let static_prefix = if method.instance { "" } else { "static " };
+    let ret = name_resolver.resolve_name(&method.return_type);
+
+    format!(
+        "{static_prefix}{ret} {full_name}::{}({params})",
+        method.name
+    )
+}
+
+/// Instance fields laid out at their offsets
+/// `None` if the layout is unknown, such as for interfaces or uninstantiated generics
+fn struct_fields(ty: &CsType, name_resolver: &CsNameResolver) -> Option<Vec<FieldSymbol>> {
+    let is_generic_template =
+        ty.generic_template.is_some() && ty.generic_instantiations_args_types.is_none();
+    if ty.is_interface || is_generic_template || ty.size_info.is_none() {
+        return None;
+    }
+
+    let fields = ty
+        .fields
+        .iter()
+        .filter(|f| f.instance && !f.is_const)
+        .filter_map(|f| {
+            Some(FieldSymbol {
+                name: sanitize_symbol(&f.name),
+                ty: name_resolver.resolve_name(&f.field_ty),
+                offset: f.offset?,
+                size: f.size,
+            })
+        })
+        .sorted_by_key(|f| f.offset)
+        .collect();
+
+    Some(fields)
+}
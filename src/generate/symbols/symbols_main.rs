@@ -0,0 +1,271 @@
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use itertools::Itertools;
+use log::info;
+
+use crate::generate::{
+    cs_context_collection::TypeContextCollection, cs_type_tag::CsTypeTag, metadata::CordlMetadata,
+};
+
+use super::symbols_data::{make_symbol_table, FieldSymbol, SymbolTable};
+
+type Result<T> = std::result::Result<T, color_eyre::eyre::Report>;
+
+///
+/// Writes the method addresses and type layouts to `folder` as
+/// a Ghidra script, an IDA script, `symbols.json` and `symbols.sym`
+///
+pub fn run_symbols(
+    cs_collection: &TypeContextCollection,
+    metadata: &CordlMetadata,
+    only: Option<&HashSet<CsTypeTag>>,
+    folder: &Path,
+    format: bool,
+) -> Result<()> {
+    let table = make_symbol_table(metadata, cs_collection, only);
+    info!(
+        "Writing {} method symbols and {} types",
+        table.methods.len(),
+        table.types.len()
+    );
+
+    fs::create_dir_all(folder)?;
+
+    write_json(&table, &folder.join("symbols.json"), format)?;
+    write_sym(&table, &folder.join("symbols.sym"))?;
+    write_ghidra(&table, &folder.join("ghidra_symbols.py"))?;
+    write_ida(&table, &folder.join("ida_symbols.idc"))?;
+
+    Ok(())
+}
+
+/// Quoted string literal, JSON escapes are valid in both Python and IDC
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap()
+}
+
+fn write_json(table: &SymbolTable, file: &Path, format: bool) -> Result<()> {
+    let mut buf_writer = BufWriter::new(File::create(file)?);
+
+    match format {
+        true => serde_json::to_writer_pretty(&mut buf_writer, table)?,
+        false => serde_json::to_writer(&mut buf_writer, table)?,
+    };
+
+    Ok(())
+}
+
+/// One `address size name` line per method, sorted by address
+fn write_sym(table: &SymbolTable, file: &Path) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(file)?);
+
+    for method in table.methods.iter().sorted_by_key(|m| m.addr) {
+        writeln!(
+            writer,
+            "{:016x} {:x} {}",
+            method.addr,
+            method.size.unwrap_or_default(),
+            method.name
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_ghidra(table: &SymbolTable, file: &Path) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(file)?);
+
+    writeln!(
+        writer,
+        "# Generated by cordl, run from the Ghidra script manager"
+    )?;
+    writeln!(writer, "# @category cordl")?;
+    writeln!(
+        writer,
+        r#"
+from ghidra.program.model.address import AddressSet
+from ghidra.program.model.data import CategoryPath, StructureDataType, Undefined
+from ghidra.program.model.symbol import SourceType
+
+base = currentProgram.getImageBase()
+dtm = currentProgram.getDataTypeManager()
+category = CategoryPath("/cordl")
+
+def define_method(addr, size, name, signature):
+    start = base.add(addr)
+    func = getFunctionAt(start)
+    if func is None:
+        func = createFunction(start, name)
+    if func is None:
+        createLabel(start, name, True)
+    else:
+        func.setName(name, SourceType.USER_DEFINED)
+        if size > 0:
+            func.setBody(AddressSet(start, start.add(size - 1)))
+    setPlateComment(start, signature)
+
+def define_struct(name, size, fields):
+    struct = StructureDataType(category, name, size, dtm)
+    for (offset, field_size, field_name, field_ty) in fields:
+        if field_size <= 0 or offset + field_size > size:
+            continue
+        struct.replaceAtOffset(offset, Undefined.getUndefinedDataType(field_size), field_size, field_name, field_ty)
+    dtm.addDataType(struct, None)
+"#
+    )?;
+
+    writeln!(writer, "methods = [")?;
+    for method in &table.methods {
+        writeln!(
+            writer,
+            "    (0x{:x}, 0x{:x}, {}, {}),",
+            method.addr,
+            method.size.unwrap_or_default(),
+            quote(&method.name),
+            quote(&method.signature)
+        )?;
+    }
+    writeln!(writer, "]")?;
+    writeln!(writer)?;
+
+    writeln!(writer, "structs = [")?;
+    for ty in &table.types {
+        let fields = ty
+            .fields
+            .iter()
+            .map(|f| {
+                format!(
+                    "(0x{:x}, 0x{:x}, {}, {})",
+                    f.offset,
+                    f.size,
+                    quote(&f.name),
+                    quote(&f.ty)
+                )
+            })
+            .join(", ");
+
+        writeln!(
+            writer,
+            "    ({}, 0x{:x}, [{fields}]),",
+            quote(&ty.name),
+            ty.size
+        )?;
+    }
+    writeln!(writer, "]")?;
+
+    writeln!(
+        writer,
+        r#"
+monitor.initialize(len(methods) + len(structs))
+for (addr, size, name, signature) in methods:
+    monitor.checkCanceled()
+    define_method(addr, size, name, signature)
+    monitor.incrementProgress(1)
+for (name, size, fields) in structs:
+    monitor.checkCanceled()
+    define_struct(name, size, fields)
+    monitor.incrementProgress(1)"#
+    )?;
+
+    Ok(())
+}
+
+/// IDA data flag for a member of `size` bytes, larger members are byte arrays
+fn ida_member_flag(field: &FieldSymbol) -> &'static str {
+    match field.size {
+        2 => "FF_WORD",
+        4 => "FF_DWORD",
+        8 => "FF_QWORD",
+        _ => "FF_BYTE",
+    }
+}
+
+fn write_ida(table: &SymbolTable, file: &Path) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(file)?);
+
+    writeln!(
+        writer,
+        r#"// Generated by cordl, run with File > Script file
+#include <idc.idc>
+
+static define_method(addr, size, name, signature) {{
+    auto start = get_imagebase() + addr;
+    if (size > 0)
+        add_func(start, start + size);
+    else
+        add_func(start, BADADDR);
+    set_name(start, name, SN_NOWARN | SN_NOCHECK | SN_FORCE);
+    set_func_cmt(start, signature, 0);
+}}
+
+static define_struct(name) {{
+    auto sid = get_struc_id(name);
+    if (sid != BADADDR)
+        del_struc(sid);
+    return add_struc(-1, name, 0);
+}}
+
+static define_methods() {{"#
+    )?;
+    for method in &table.methods {
+        writeln!(
+            writer,
+            "    define_method(0x{:x}, 0x{:x}, {}, {});",
+            method.addr,
+            method.size.unwrap_or_default(),
+            quote(&method.name),
+            quote(&method.signature)
+        )?;
+    }
+    writeln!(writer, "}}")?;
+    writeln!(writer)?;
+
+    writeln!(writer, "static define_structs() {{")?;
+    writeln!(writer, "    auto sid;")?;
+    for ty in &table.types {
+        writeln!(writer, "    sid = define_struct({});", quote(&ty.name))?;
+
+        let mut end = 0;
+        for field in ty.fields.iter().filter(|f| f.size > 0) {
+            // overlapping fields (explicit layout) can't be represented
+            if field.offset < end {
+                continue;
+            }
+            writeln!(
+                writer,
+                "    add_struc_member(sid, {}, 0x{:x}, FF_DATA | {}, -1, 0x{:x});",
+                quote(&field.name),
+                field.offset,
+                ida_member_flag(field),
+                field.size
+            )?;
+            end = field.offset + field.size as u32;
+        }
+
+        // pad to the full size of the type
+        if end < ty.size {
+            writeln!(
+                writer,
+                "    add_struc_member(sid, \"_padding\", 0x{end:x}, FF_DATA | FF_BYTE, -1, 0x{:x});",
+                ty.size - end
+            )?;
+        }
+    }
+    writeln!(writer, "}}")?;
+
+    writeln!(
+        writer,
+        r#"
+static main() {{
+    define_methods();
+    define_structs();
+}}"#
+    )?;
+
+    Ok(())
+}
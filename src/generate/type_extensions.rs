@@ -13,21 +13,27 @@ pub const PARAM_ATTRIBUTE_IN: u16 = 0x0001;
 pub const PARAM_ATTRIBUTE_OUT: u16 = 0x0002;
 pub const PARAM_ATTRIBUTE_OPTIONAL: u16 = 0x0010;
 
+pub const TYPE_ATTRIBUTE_VISIBILITY_MASK: u32 = 0x00000007;
 pub const TYPE_ATTRIBUTE_INTERFACE: u32 = 0x00000020;
+pub const TYPE_ATTRIBUTE_ABSTRACT: u32 = 0x00000080;
+pub const TYPE_ATTRIBUTE_SEALED: u32 = 0x00000100;
 pub const TYPE_ATTRIBUTE_NESTED_PUBLIC: u32 = 0x00000002;
 pub const TYPE_ATTRIBUTE_EXPLICIT_LAYOUT: u32 = 0x00000010;
 pub const TYPE_ATTRIBUTE_SPECIAL_NAME: u32 = 0x00000400;
 
+pub const FIELD_ATTRIBUTE_FIELD_ACCESS_MASK: u16 = 0x0007;
 pub const FIELD_ATTRIBUTE_PUBLIC: u16 = 0x0006;
 pub const FIELD_ATTRIBUTE_PRIVATE: u16 = 0x0001;
 pub const FIELD_ATTRIBUTE_STATIC: u16 = 0x0010;
 pub const FIELD_ATTRIBUTE_LITERAL: u16 = 0x0040;
 
+pub const METHOD_ATTRIBUTE_MEMBER_ACCESS_MASK: u16 = 0x0007;
 pub const METHOD_ATTRIBUTE_PUBLIC: u16 = 0x0006;
 pub const METHOD_ATTRIBUTE_STATIC: u16 = 0x0010;
 pub const METHOD_ATTRIBUTE_FINAL: u16 = 0x0020;
 pub const METHOD_ATTRIBUTE_VIRTUAL: u16 = 0x0040;
 pub const METHOD_ATTRIBUTE_HIDE_BY_SIG: u16 = 0x0080;
+pub const METHOD_ATTRIBUTE_NEW_SLOT: u16 = 0x0100;
 pub const METHOD_ATTRIBUTE_ABSTRACT: u16 = 0x0400;
 pub const METHOD_ATTRIBUTE_SPECIAL_NAME: u16 = 0x0800;
 
@@ -39,6 +45,7 @@ pub trait MethodDefintionExtensions {
     fn is_hidden_sig(&self) -> bool;
     fn is_special_name(&self) -> bool;
     fn is_final_method(&self) -> bool;
+    fn is_override_method(&self) -> bool;
 }
 
 impl MethodDefintionExtensions for Il2CppMethodDefinition {
@@ -69,12 +76,18 @@ impl MethodDefintionExtensions for Il2CppMethodDefinition {
     fn is_final_method(&self) -> bool {
         (self.flags & METHOD_ATTRIBUTE_FINAL) != 0
     }
+
+    /// Virtual methods reuse the slot of the method they override unless marked `newslot`
+    fn is_override_method(&self) -> bool {
+        self.is_virtual_method() && (self.flags & METHOD_ATTRIBUTE_NEW_SLOT) == 0
+    }
 }
 
 pub trait ParameterDefinitionExtensions {
     fn is_param_optional(&self) -> bool;
     fn is_param_in(&self) -> bool;
     fn is_param_out(&self) -> bool;
+    fn param_flags(&self) -> CsParamFlags;
 }
 
 impl ParameterDefinitionExtensions for Il2CppType {
@@ -89,6 +102,19 @@ impl ParameterDefinitionExtensions for Il2CppType {
     fn is_param_out(&self) -> bool {
         (self.attrs & PARAM_ATTRIBUTE_OUT) != 0
     }
+
+    /// `ref`, `in` or `out`, only byref parameters have modifiers
+    fn param_flags(&self) -> CsParamFlags {
+        if !self.byref {
+            return CsParamFlags::empty();
+        }
+
+        match (self.is_param_in(), self.is_param_out()) {
+            (true, false) => CsParamFlags::IN,
+            (false, true) => CsParamFlags::OUT,
+            _ => CsParamFlags::REF,
+        }
+    }
 }
 
 pub trait TypeExtentions {
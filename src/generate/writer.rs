@@ -1,10 +1,8 @@
-use std::{
-    fs::File,
-    io::{BufWriter, Write},
-};
+use std::io::Write;
 
+/// Renders a file to memory, so it is only written to disk if it changed
 pub struct Writer {
-    pub stream: BufWriter<File>,
+    pub stream: Vec<u8>,
     pub indent: u16,
     pub newline: bool,
 }
@@ -0,0 +1,6 @@
+/// FNV-1a, stable between runs and platforms unlike the std hasher
+pub fn stable_hash(content: &[u8]) -> u64 {
+    content.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}